@@ -4,14 +4,17 @@ fn main() {
     cc::Build::new()
         .file("src/controllers/mount/controller/MountControl0.c")
         .file("src/controllers/mount/controller/MountControl0_data.c")
+        .file("src/controllers/mount/controller/MountControl0_state.c")
         .compile("mount_controller");
     cc::Build::new()
         .file("src/controllers/mount/pdr/controller/Mount_Control.c")
         .file("src/controllers/mount/pdr/controller/Mount_Control_data.c")
+        .file("src/controllers/mount/pdr/controller/Mount_Control_state.c")
         .compile("mount_pdr_controller");
     cc::Build::new()
         .file("src/controllers/mount/drives/MountDrives.c")
         .file("src/controllers/mount/drives/MountDrives_data.c")
+        .file("src/controllers/mount/drives/MountDrives_state.c")
         .compile("mount_drives");
     cc::Build::new()
         .file("src/controllers/mount/pdr/drives/Mount_Drv_PDR2021.c")
@@ -19,16 +22,20 @@ fn main() {
         .file("src/controllers/mount/pdr/drives/rtGetInf.c")
         .file("src/controllers/mount/pdr/drives/rtGetNaN.c")
         .file("src/controllers/mount/pdr/drives/rt_nonfinite.c")
+        .file("src/controllers/mount/pdr/drives/Mount_Drv_PDR2021_state.c")
         .compile("mount_pdr_drives");
     cc::Build::new()
         .file("src/controllers/m1/hp_load_cells/M1HPloadcells.c")
+        .file("src/controllers/m1/hp_load_cells/M1HPloadcells_state.c")
         .compile("m1_hp_load_cells");
     cc::Build::new()
         .file("src/controllers/m1/local_controller/M1LocalControl.c")
         .file("src/controllers/m1/local_controller/M1LocalControl_data.c")
+        .file("src/controllers/m1/local_controller/M1LocalControl_state.c")
         .compile("m1_local_controller");
     cc::Build::new()
         .file("src/controllers/m1/cg_controller/M1OFL_Control.c")
         .file("src/controllers/m1/cg_controller/M1OFL_Control_data.c")
+        .file("src/controllers/m1/cg_controller/M1OFL_Control_state.c")
         .compile("m1_cg_controller");
 }
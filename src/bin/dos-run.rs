@@ -0,0 +1,314 @@
+use dos::{
+    config::{ControllerConfig, ControllerKind, LoggingConfig, SimulationConfig, WindLoadsConfig},
+    controllers::{
+        linear::{low_pass, notch, Delay, LinearBlock, Pid, Solver, TransferFunction},
+        m1,
+        mount::pdr as mount,
+        state_space::DiscreteStateSpace,
+        SummingJunction,
+    },
+    io::{Tags, IO},
+    model::Model,
+    telltale::TellTale,
+    wind_loads::LoadsMapping,
+    DOSIOSError, DataLogging, IOTags, WindLoading, WindLoads, DOS,
+};
+use fem::FEM;
+use serde_pickle as pkl;
+use simple_logger::SimpleLogger;
+use std::{error::Error, fs::File, path::Path, time::Instant};
+
+struct Timer {
+    time: Instant,
+}
+impl Timer {
+    pub fn tic() -> Self {
+        Self {
+            time: Instant::now(),
+        }
+    }
+    pub fn toc(self) -> f64 {
+        self.time.elapsed().as_secs_f64()
+    }
+    pub fn print_toc(self) {
+        println!("... in {:3}s", self.toc());
+    }
+}
+
+/// Controller components of the configuration
+enum Component {
+    MountDrives(mount::drives::Controller<'static>),
+    MountController(mount::controller::Controller<'static>),
+    M1HardpointsLoadCells(m1::hp_load_cells::Controller<'static>),
+    M1CgController(m1::cg_controller::Controller<'static>),
+    Pid(LinearBlock<Pid>),
+    TransferFunction(LinearBlock<TransferFunction>),
+    Delay(LinearBlock<Delay>),
+    SummingJunction(SummingJunction),
+}
+macro_rules! dispatch {
+    ($controller:expr, $c:ident => $e:expr) => {
+        match $controller {
+            Component::MountDrives($c) => $e,
+            Component::MountController($c) => $e,
+            Component::M1HardpointsLoadCells($c) => $e,
+            Component::M1CgController($c) => $e,
+            Component::Pid($c) => $e,
+            Component::TransferFunction($c) => $e,
+            Component::Delay($c) => $e,
+            Component::SummingJunction($c) => $e,
+        }
+    };
+}
+impl Component {
+    fn new(config: &ControllerConfig, sampling_rate: f64) -> Self {
+        let fs = config.sampling_rate(sampling_rate);
+        match config.kind.clone() {
+            ControllerKind::MountDrives => Self::MountDrives(mount::drives::Controller::new()),
+            ControllerKind::MountController => {
+                Self::MountController(mount::controller::Controller::new())
+            }
+            ControllerKind::M1HardpointsLoadCells => {
+                Self::M1HardpointsLoadCells(m1::hp_load_cells::Controller::new())
+            }
+            ControllerKind::M1CgController => {
+                Self::M1CgController(m1::cg_controller::Controller::new())
+            }
+            ControllerKind::Pid {
+                input,
+                output,
+                kp,
+                ki,
+                kd,
+                saturation,
+                derivative_filter,
+            } => {
+                let mut pid = Pid::new(kp, ki, kd, 1f64 / fs);
+                if let Some((min, max)) = saturation {
+                    pid = pid.saturation(min, max);
+                }
+                if let Some(tf) = derivative_filter {
+                    pid = pid.derivative_filter(tf);
+                }
+                Self::Pid(pid.block(input, output))
+            }
+            ControllerKind::TransferFunction {
+                input,
+                output,
+                num,
+                den,
+            } => Self::TransferFunction(TransferFunction::new(num, den).block(input, output)),
+            ControllerKind::LowPass {
+                input,
+                output,
+                cutoff_frequency,
+            } => Self::TransferFunction(low_pass(cutoff_frequency, fs).block(input, output)),
+            ControllerKind::Notch {
+                input,
+                output,
+                frequency,
+                q,
+            } => Self::TransferFunction(notch(frequency, q, fs).block(input, output)),
+            ControllerKind::Delay { input, output, n } => {
+                Self::Delay(Delay::new(n).block(input, output))
+            }
+            ControllerKind::SummingJunction { output, inputs } => Self::SummingJunction(
+                inputs
+                    .into_iter()
+                    .fold(SummingJunction::new(output), |junction, input| {
+                        junction.gain(input.io, input.gain)
+                    }),
+            ),
+        }
+    }
+}
+impl Iterator for Component {
+    type Item = ();
+    fn next(&mut self) -> Option<Self::Item> {
+        dispatch!(self, c => c.next().map(|_| ()))
+    }
+}
+impl IOTags for Component {
+    fn outputs_tags(&self) -> Vec<Tags> {
+        dispatch!(self, c => c.outputs_tags())
+    }
+    fn inputs_tags(&self) -> Vec<Tags> {
+        dispatch!(self, c => c.inputs_tags())
+    }
+}
+impl DOS for Component {
+    fn inputs(&mut self, data: Vec<IO<Vec<f64>>>) -> Result<&mut Self, DOSIOSError> {
+        dispatch!(self, c => { c.inputs(data)?; });
+        Ok(self)
+    }
+    fn outputs(&mut self) -> Option<Vec<IO<Vec<f64>>>> {
+        dispatch!(self, c => c.outputs())
+    }
+}
+
+fn wind_loading(
+    config: &WindLoadsConfig,
+    sampling_rate: f64,
+) -> Result<WindLoading, Box<dyn Error>> {
+    let mut loads = match config.path.extension().and_then(|x| x.to_str()) {
+        #[cfg(feature = "apache-arrow")]
+        Some("parquet") => WindLoads::from_parquet(&config.path)?,
+        _ => WindLoads::from_pickle(&config.path)?,
+    };
+    if let Some((t_min, t_max)) = config.range {
+        loads = loads.range(t_min, t_max);
+    }
+    if let Some(scaling) = &config.wind_speed_scaling {
+        loads = loads.scale_wind_speed(scaling.cfd_wind_speed, scaling.wind_speed);
+    }
+    if (loads.sampling_rate()? / sampling_rate - 1f64).abs() > 1e-6 {
+        loads = loads.resample(sampling_rate)?;
+    }
+    if config.remove_mean {
+        loads = loads.remove_mean();
+    }
+    if let Some(looping) = &config.looping {
+        loads = loads.looping(looping.duration, looping.crossfade)?;
+    }
+    if let Some(duration) = config.ramp_in {
+        loads = loads.ramp_in(duration);
+    }
+    for transform in &config.transforms {
+        let nodes: Vec<_> = transform.nodes.iter().map(|x| x.into()).collect();
+        loads = loads.transform(transform.source.clone(), &nodes)?;
+    }
+    let loads = if config.mappings.is_empty() {
+        loads.select_all()?
+    } else {
+        let mappings: Vec<LoadsMapping> = config.mappings.iter().map(|x| x.into()).collect();
+        loads.map(&mappings)?
+    };
+    Ok(loads.build()?)
+}
+
+fn data_logging(config: &LoggingConfig, sampling_rate: f64) -> Result<TellTale, Box<dyn Error>> {
+    let mut data = DataLogging::new().sampling_rate(sampling_rate);
+    for key in &config.keys {
+        data = data.decimated_key(key.io.clone(), key.decimation);
+    }
+    for statistics in &config.statistics {
+        data = data.statistics(statistics.io.clone(), statistics.into());
+    }
+    if let Some(stream) = &config.stream {
+        data = data.stream(&stream.path, stream.chunk_size)?;
+    }
+    Ok(data.build())
+}
+
+fn save(config: &LoggingConfig, data: &mut TellTale) -> Result<(), Box<dyn Error>> {
+    data.flush()?;
+    if let Some(path) = &config.output {
+        match path.extension().and_then(|x| x.to_str()) {
+            #[cfg(feature = "apache-arrow")]
+            Some("parquet") => data.to_parquet(path)?,
+            _ => {
+                let time_series: Vec<_> = config
+                    .keys
+                    .iter()
+                    .map(|key| data.time_series(key.io.clone()))
+                    .collect();
+                let mut f = File::create(path)?;
+                pkl::to_writer(&mut f, &time_series, true)?;
+            }
+        }
+    }
+    if let Some(path) = &config.statistics_output {
+        match path.extension().and_then(|x| x.to_str()) {
+            Some("json") => data.statistics().to_json(path)?,
+            _ => data.statistics().to_pickle(path)?,
+        }
+    }
+    Ok(())
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    SimpleLogger::new().init().unwrap();
+    let path = std::env::args()
+        .nth(1)
+        .ok_or("usage: dos-run <simulation.toml|simulation.yaml>")?;
+    let config = SimulationConfig::from_path(Path::new(&path))?;
+    let fs = config.sampling_rate;
+
+    // WIND LOADS
+    let mut wind_loading = match &config.wind_loads {
+        Some(wind_loads) => {
+            let tic = Timer::tic();
+            println!("Loading wind loads ...");
+            let wind_loading = wind_loading(wind_loads, fs)?;
+            tic.print_toc();
+            Some(wind_loading)
+        }
+        None => None,
+    };
+
+    // CONTROLLERS
+    let mut controllers: Vec<_> = config
+        .controllers
+        .iter()
+        .map(|c| Component::new(c, fs))
+        .collect();
+
+    // FEM
+    let tic = Timer::tic();
+    println!("Building FEM dynamic model...");
+    let fem = FEM::from_pickle(&config.fem.path)?;
+    if let Some(wind_loading) = &wind_loading {
+        wind_loading.check_fem_inputs(&fem)?;
+    }
+    let mut fem_ss = DiscreteStateSpace::from(fem).sampling(fs);
+    if let Some(zeta) = config.fem.proportional_damping {
+        fem_ss = fem_ss.proportional_damping(zeta);
+    }
+    if !config.fem.eigen_frequencies.is_empty() {
+        fem_ss = fem_ss.eigen_frequencies(config.fem.eigen_frequencies.clone());
+    }
+    if let Some(max_eigen_frequency) = config.fem.max_eigen_frequency {
+        fem_ss = fem_ss.max_eigen_frequency(max_eigen_frequency);
+    }
+    if let Some(path) = &config.fem.dump_eigen_frequencies {
+        fem_ss = fem_ss.dump_eigen_frequencies(path);
+    }
+    if let Some(wind_loading) = &wind_loading {
+        fem_ss = fem_ss.inputs_from(wind_loading);
+    }
+    let mut fem = fem_ss
+        .inputs(config.fem.inputs.clone())
+        .outputs(config.fem.outputs.clone())
+        .build()?;
+    tic.print_toc();
+
+    // DATA LOGGING
+    let mut data = data_logging(&config.logging, fs)?;
+
+    // MODEL
+    let mut model = Model::new();
+    if let Some(wind_loading) = wind_loading.as_mut() {
+        model = model.source("wind loads", wind_loading);
+    }
+    model = model.component("FEM", &mut fem);
+    for (controller, config) in controllers.iter_mut().zip(config.controllers.iter()) {
+        model = model.decimated_component(&config.name, controller, config.decimation(fs)?);
+    }
+    model = config
+        .connections
+        .initial
+        .iter()
+        .fold(model, |model, initial| model.initial(initial.into()));
+    let mut simulation = model.component("data logging", &mut data).build()?;
+    println!("{}", simulation.report());
+
+    println!("Running model ...");
+    let tic = Timer::tic();
+    let n_step = simulation.run(config.n_step())?;
+    println!("{} time steps", n_step);
+    tic.print_toc();
+    drop(simulation);
+
+    // OUTPUTS SAVING
+    save(&config.logging, &mut data)
+}
@@ -32,6 +32,7 @@ fn main() -> Result<(), Box<dyn Error>> {
     println!("Loading wind loads ...");
     let n_sample = 19990;
     let mut wind_loading = WindLoads::from_pickle("data/trimmer_finest_mesh_20Hz.neu.pkl")?
+        .resample(2e3)?
         .n_sample(n_sample)?
         .select_all()?
         .build()?;
@@ -84,8 +84,8 @@ fn main() -> Result<(), DOSError> {
     // DATA LOGGING
     let mut data = DataLogging::new()
         .sampling_rate(sampling_rate)
-        //.key(m1_rbm.clone())
-        //.key(m2_rbm.clone())
+        .key(m1_rbm.clone())
+        .key(m2_rbm.clone())
         .build();
 
     println!("Running model ...");
@@ -127,8 +127,7 @@ fn main() -> Result<(), DOSError> {
                 .unwrap()?;
         }
         // DATA LOGGING
-        data.step()?;
-        data.log(&fem_outputs[0])?.log(&fem_outputs[1])?;
+        data.inputs(fem_outputs)?.step()?;
         k += 1;
     }
     tic.print_toc();
@@ -2,6 +2,7 @@ use dos::{
     controllers::{m1, mount::pdr as mount, state_space::DiscreteStateSpace},
     io::jar::*,
     io::IO,
+    wind_loads::cfd::CfdCatalogue,
     DataLogging, WindLoads, DOS,
 };
 use fem::FEM;
@@ -88,8 +89,8 @@ fn job(cfd_case: &str) -> Result<(), Box<dyn Error>> {
     // DATA LOGGING
     let mut data = DataLogging::new()
         .sampling_rate(sampling_rate)
-        //.key(m1_rbm.clone())
-        //.key(m2_rbm.clone())
+        .key(m1_rbm.clone())
+        .key(m2_rbm.clone())
         .build();
 
     println!("Running model ...");
@@ -131,8 +132,7 @@ fn job(cfd_case: &str) -> Result<(), Box<dyn Error>> {
                 .unwrap()?;
         }
         // DATA LOGGING
-        data.step()?;
-        data.log(&fem_outputs[0])?.log(&fem_outputs[1])?;
+        data.inputs(fem_outputs)?.step()?;
         k += 1;
     }
     tic.print_toc();
@@ -161,72 +161,12 @@ fn job(cfd_case: &str) -> Result<(), Box<dyn Error>> {
 }
 
 fn main() {
-    let cfd_cases = vec![
-        "b2019_0z_0az_os_2ms",
-        "b2019_0z_0az_os_7ms",
-        "b2019_0z_0az_cd_12ms",
-        "b2019_0z_0az_cd_17ms",
-        "b2019_0z_45az_os_2ms",
-        "b2019_0z_45az_os_7ms",
-        "b2019_0z_45az_cd_12ms",
-        "b2019_0z_45az_cd_17ms",
-        "b2019_0z_90az_os_2ms",
-        "b2019_0z_90az_os_7ms",
-        "b2019_0z_90az_cd_12ms",
-        "b2019_0z_90az_cd_17ms",
-        "b2019_0z_135az_os_2ms",
-        "b2019_0z_135az_os_7ms",
-        "b2019_0z_135az_cd_12ms",
-        "b2019_0z_135az_cd_17ms",
-        "b2019_0z_180az_os_2ms",
-        "b2019_0z_180az_os_7ms",
-        "b2019_0z_180az_cd_12ms",
-        "b2019_0z_180az_cd_17ms",
-        "b2019_30z_0az_os_2ms",
-        "b2019_30z_0az_os_7ms",
-        "b2019_30z_0az_cd_12ms",
-        "b2019_30z_0az_cd_17ms",
-        "b2019_30z_45az_os_2ms",
-        "b2019_30z_45az_os_7ms",
-        "b2019_30z_45az_cd_12ms",
-        "b2019_30z_45az_cd_17ms",
-        "b2019_30z_90az_os_2ms",
-        "b2019_30z_90az_os_7ms",
-        "b2019_30z_90az_cd_12ms",
-        "b2019_30z_90az_cd_17ms",
-        "b2019_30z_135az_os_2ms",
-        "b2019_30z_135az_os_7ms",
-        "b2019_30z_135az_cd_12ms",
-        "b2019_30z_135az_cd_17ms",
-        "b2019_30z_180az_os_2ms",
-        "b2019_30z_180az_os_7ms",
-        "b2019_30z_180az_cd_12ms",
-        "b2019_30z_180az_cd_17ms",
-        "b2019_60z_0az_os_2ms",
-        "b2019_60z_0az_os_7ms",
-        "b2019_60z_0az_cd_12ms",
-        "b2019_60z_0az_cd_17ms",
-        "b2019_60z_45az_os_2ms",
-        "b2019_60z_45az_os_7ms",
-        "b2019_60z_45az_cd_12ms",
-        "b2019_60z_45az_cd_17ms",
-        "b2019_60z_90az_os_2ms",
-        "b2019_60z_90az_os_7ms",
-        "b2019_60z_90az_cd_12ms",
-        "b2019_60z_90az_cd_17ms",
-        "b2019_60z_135az_os_2ms",
-        "b2019_60z_135az_os_7ms",
-        "b2019_60z_135az_cd_12ms",
-        "b2019_60z_135az_cd_17ms",
-        "b2019_60z_180az_os_2ms",
-        "b2019_60z_180az_os_7ms",
-        "b2019_60z_180az_cd_12ms",
-        "b2019_60z_180az_cd_17ms",
-    ];
-    cfd_cases
-        .into_par_iter()
-        .for_each(|cfd_case| match job(cfd_case) {
+    let catalogue = CfdCatalogue::baseline2019(Path::new("/fsx").join("Baseline2020"));
+    catalogue.cases().par_iter().for_each(|cfd_case| {
+        let cfd_case = cfd_case.to_string();
+        match job(&cfd_case) {
             Ok(_) => println!("{} succeed!!!", cfd_case),
             Err(_) => println!("{} failed!?!", cfd_case),
-        })
+        }
+    })
 }
@@ -0,0 +1,69 @@
+//! Compares the wind loads statistics of 2 CFD cases
+//!
+//! Usage: `wind_loads_compare <case A wind loads pickle file> <case B wind loads pickle file> [n_fft]`
+//!
+//! The statistics of both cases are written to `wind_loads_stats_A.json` and `wind_loads_stats_B.json`.
+
+use dos::{wind_loads::statistics::Statistics, WindLoads};
+use std::{env, error::Error};
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 3 {
+        return Err(
+            "usage: wind_loads_compare <case A wind loads pickle file> <case B wind loads pickle file> [n_fft]"
+                .into(),
+        );
+    }
+    let n_fft = args.get(3).map_or(Ok(1024), |x| x.parse::<usize>())?;
+
+    let stats = [&args[1], &args[2]]
+        .iter()
+        .zip(["A", "B"].iter())
+        .map(|(path, case)| {
+            println!("Case {}: {}", case, path);
+            let stats = WindLoads::from_pickle(path)?.statistics(n_fft)?;
+            stats.to_json(format!("wind_loads_stats_{}.json", case))?;
+            Ok(stats)
+        })
+        .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+
+    let print = |label: &str, a: &[f64], b: &[f64]| {
+        a.iter().zip(b.iter()).enumerate().for_each(|(i, (a, b))| {
+            println!(
+                "  {:>5}[{:>2}]: {:>+12.3e} {:>+12.3e} {:>+9.3}",
+                label,
+                i,
+                a,
+                b,
+                if *a == 0f64 { f64::NAN } else { b / a }
+            )
+        })
+    };
+    println!("{:>14} {:>12} {:>12} {:>9}", "", "A", "B", "B/A");
+    for a in &stats[0].loads {
+        match stats[1].get(&a.name) {
+            Some(b) => {
+                println!("{}", a.name);
+                let Statistics {
+                    mean,
+                    std,
+                    min,
+                    max,
+                    ..
+                } = a;
+                print("mean", mean, &b.mean);
+                print("std", std, &b.std);
+                print("min", min, &b.min);
+                print("max", max, &b.max);
+            }
+            None => println!("{}: missing in case B", a.name),
+        }
+    }
+    stats[1]
+        .loads
+        .iter()
+        .filter(|b| stats[0].get(&b.name).is_none())
+        .for_each(|b| println!("{}: missing in case A", b.name));
+    Ok(())
+}
@@ -0,0 +1,912 @@
+//! Simulation configuration
+//!
+//! A [`SimulationConfig`] describes a whole simulation in a TOML or YAML file:
+//!  - the simulation sampling rate and duration,
+//!  - the FEM file and the [`DiscreteStateSpace`](crate::controllers::state_space::DiscreteStateSpace) options and inputs/outputs,
+//!  - the wind loads file, the wind loads time range, wind speed scaling, windowing, coordinate transforms and mapping to the FEM inputs,
+//!  - the controllers and their update rates,
+//!  - the initial values of the delayed or unconnected inputs,
+//!  - the logged keys, their decimation, online statistics and output files.
+//!
+//! The [`IO`] are given by their names, e.g. `OSSM1Lcl`, see [`io_tag`].
+//! The components are connected by a [`Model`](crate::model::Model) according to their inputs and outputs,
+//! a [`SummingJunction`](crate::controllers::SummingJunction) with a single input is used to feed the output of a component into an input with a different name.
+//!
+//! The simulation is run with the `dos-run` binary: `dos-run simulation.toml`.
+//!
+//! # Example
+//! ```no_run
+//! use dos::config::SimulationConfig;
+//!
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let config = SimulationConfig::from_toml(
+//!         r#"
+//! sampling_rate = 1000.0
+//!
+//! [fem]
+//! path = "data/modal_state_space_model_2ndOrder.pkl"
+//! proportional_damping = 0.02
+//! inputs = ["OSSAzDriveTorque", "OSSElDriveTorque", "OSSRotDriveTorque"]
+//! outputs = ["OSSAzEncoderAngle", "OSSElEncoderAngle", "OSSRotEncoderAngle", "OSSM1Lcl"]
+//!
+//! [wind_loads]
+//! path = "data/wind_loads.pkl"
+//! range = [100.0, 400.0]
+//! ramp_in = 1.0
+//! mappings = [
+//!     { key = "OSS_Truss_6F", target = "OSSTruss6F" },
+//!     { key = "OSS_M1_lcl_6F", target = "OSSM1Lcl6F" },
+//! ]
+//!
+//! [[controllers]]
+//! name = "mount controller"
+//! kind = "mount_controller"
+//! rate = 1000.0
+//!
+//! [[controllers]]
+//! name = "mount drives"
+//! kind = "mount_drives"
+//!
+//! # the feedback loop is broken at the FEM inputs
+//! [connections]
+//! initial = [
+//!     { io = "OSSAzDriveTorque", size = 12 },
+//!     { io = "OSSElDriveTorque", size = 4 },
+//!     { io = "OSSRotDriveTorque", size = 4 },
+//! ]
+//!
+//! [logging]
+//! keys = [{ io = "OSSM1Lcl" }, { io = "OSSAzEncoderAngle", decimation = 10 }]
+//! statistics = [{ io = "OSSM1Lcl", n_fft = 4096 }]
+//! output = "data/simulation.pkl"
+//! statistics_output = "data/statistics.json"
+//! "#,
+//!     )?;
+//!     Ok(())
+//! }
+//! ```
+
+use crate::{
+    io::{io_tag, Tags},
+    telltale::accumulators::OnlineStatistics,
+    wind_loads::{transform::NodeTransform, LoadsMapping},
+    IO,
+};
+use serde::{de, Deserialize, Deserializer};
+use std::{cmp::Ordering, collections::HashSet, fmt, fs, io, path::Path, path::PathBuf};
+
+#[derive(Debug)]
+pub enum ConfigError {
+    File(io::Error),
+    Toml(toml::de::Error),
+    Yaml(serde_yaml::Error),
+    Format(String),
+    Invalid(String),
+}
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::File(e) => write!(f, "cannot read the configuration file: {}", e),
+            Self::Toml(e) => write!(f, "invalid TOML configuration: {}", e),
+            Self::Yaml(e) => write!(f, "invalid YAML configuration: {}", e),
+            Self::Format(e) => write!(f, "unknown configuration format: {}", e),
+            Self::Invalid(e) => write!(f, "invalid configuration: {}", e),
+        }
+    }
+}
+impl From<io::Error> for ConfigError {
+    fn from(e: io::Error) -> Self {
+        Self::File(e)
+    }
+}
+impl From<toml::de::Error> for ConfigError {
+    fn from(e: toml::de::Error) -> Self {
+        Self::Toml(e)
+    }
+}
+impl From<serde_yaml::Error> for ConfigError {
+    fn from(e: serde_yaml::Error) -> Self {
+        Self::Yaml(e)
+    }
+}
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::File(source) => Some(source),
+            Self::Toml(source) => Some(source),
+            Self::Yaml(source) => Some(source),
+            _ => None,
+        }
+    }
+}
+
+type Result<T> = std::result::Result<T, ConfigError>;
+
+/// Deserializes an [`IO`] tag from its name
+fn tag<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Tags, D::Error> {
+    let name = String::deserialize(deserializer)?;
+    io_tag(&name).ok_or_else(|| de::Error::custom(format!("unknown IO {}", name)))
+}
+/// Deserializes a list of [`IO`] tags from their names
+fn tags<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Vec<Tags>, D::Error> {
+    Vec::<String>::deserialize(deserializer)?
+        .into_iter()
+        .map(|name| io_tag(&name).ok_or_else(|| de::Error::custom(format!("unknown IO {}", name))))
+        .collect()
+}
+
+/// Simulation configuration
+#[derive(Debug, Clone, Deserialize)]
+pub struct SimulationConfig {
+    /// sampling rate in Hz
+    pub sampling_rate: f64,
+    /// duration in seconds, the simulation ends with the wind loads if `None`
+    pub duration: Option<f64>,
+    pub fem: FemConfig,
+    pub wind_loads: Option<WindLoadsConfig>,
+    #[serde(default)]
+    pub controllers: Vec<ControllerConfig>,
+    #[serde(default)]
+    pub connections: ConnectionsConfig,
+    #[serde(default)]
+    pub logging: LoggingConfig,
+}
+impl SimulationConfig {
+    /// Reads the configuration from a TOML (`.toml`) or a YAML (`.yaml` or `.yml`) file
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path)?;
+        match path.extension().and_then(|x| x.to_str()) {
+            Some("toml") => Self::from_toml(&contents),
+            Some("yaml") | Some("yml") => Self::from_yaml(&contents),
+            _ => Err(ConfigError::Format(path.display().to_string())),
+        }
+    }
+    /// Parses a TOML configuration
+    pub fn from_toml(contents: &str) -> Result<Self> {
+        toml::from_str::<Self>(contents)?.check()
+    }
+    /// Parses a YAML configuration
+    pub fn from_yaml(contents: &str) -> Result<Self> {
+        serde_yaml::from_str::<Self>(contents)?.check()
+    }
+    /// Returns the number of time steps of the simulation if the duration is set
+    pub fn n_step(&self) -> Option<usize> {
+        self.duration
+            .map(|duration| (duration * self.sampling_rate).round() as usize)
+    }
+    fn check(self) -> Result<Self> {
+        if !(self.sampling_rate.is_finite() && self.sampling_rate > 0f64) {
+            return Err(ConfigError::Invalid(
+                "the sampling rate must be greater than 0".into(),
+            ));
+        }
+        if self.duration.is_none() && self.wind_loads.is_none() {
+            return Err(ConfigError::Invalid(
+                "the duration must be set if there are no wind loads".into(),
+            ));
+        }
+        let mut names: HashSet<&str> = ["wind loads", "FEM", "data logging"]
+            .iter()
+            .cloned()
+            .collect();
+        let mut simulink = HashSet::new();
+        for controller in &self.controllers {
+            if !names.insert(controller.name.as_str()) {
+                return Err(ConfigError::Invalid(format!(
+                    "the component name {} is used more than once",
+                    controller.name
+                )));
+            }
+            // the Simulink controllers states are global variables shared by all the instances
+            if controller.kind.is_simulink() && !simulink.insert(controller.kind.name()) {
+                return Err(ConfigError::Invalid(format!(
+                    "the Simulink controller {} is used more than once",
+                    controller.kind.name()
+                )));
+            }
+            controller.decimation(self.sampling_rate)?;
+        }
+        if let Some(initial) = self
+            .connections
+            .initial
+            .iter()
+            .find(|x| x.size.is_none() && x.value.is_none())
+        {
+            return Err(ConfigError::Invalid(format!(
+                "the initial value of {:?} has neither size nor value",
+                initial.io
+            )));
+        }
+        if self.logging.stream.is_some() && self.logging.output.is_some() {
+            return Err(ConfigError::Invalid(
+                "the logged data are either streamed or written to the output file".into(),
+            ));
+        }
+        if let Some(key) = self.logging.keys.iter().find(|x| x.decimation == 0) {
+            return Err(ConfigError::Invalid(format!(
+                "the decimation of the logged key {:?} must be greater than 0",
+                key.io
+            )));
+        }
+        if self
+            .logging
+            .stream
+            .as_ref()
+            .is_some_and(|x| x.chunk_size == 0)
+        {
+            return Err(ConfigError::Invalid(
+                "the stream chunk size must be greater than 0".into(),
+            ));
+        }
+        for statistics in &self.logging.statistics {
+            if statistics.n_fft.is_some_and(|n_fft| n_fft < 2) {
+                return Err(ConfigError::Invalid(format!(
+                    "the PSD segments size of {:?} must be greater than 1",
+                    statistics.io
+                )));
+            }
+            if statistics
+                .histogram
+                .as_ref()
+                .is_some_and(|h| h.n_bin == 0 || h.min.partial_cmp(&h.max) != Some(Ordering::Less))
+            {
+                return Err(ConfigError::Invalid(format!(
+                    "the histogram of {:?} must have at least 1 bin and max greater than min",
+                    statistics.io
+                )));
+            }
+        }
+        Ok(self)
+    }
+}
+
+/// FEM state space model configuration
+#[derive(Debug, Clone, Deserialize)]
+pub struct FemConfig {
+    /// FEM pickle file
+    pub path: PathBuf,
+    pub proportional_damping: Option<f64>,
+    pub max_eigen_frequency: Option<f64>,
+    /// overwritten eigen frequencies: mode index and frequency in Hz
+    #[serde(default)]
+    pub eigen_frequencies: Vec<(usize, f64)>,
+    /// eigen frequencies pickle file
+    pub dump_eigen_frequencies: Option<PathBuf>,
+    /// inputs in addition to the wind loads
+    #[serde(default, deserialize_with = "tags")]
+    pub inputs: Vec<Tags>,
+    #[serde(deserialize_with = "tags")]
+    pub outputs: Vec<Tags>,
+}
+
+/// Wind loads configuration
+///
+/// The wind loads are resampled at the simulation sampling rate if need be and processed in the order of the fields
+#[derive(Debug, Clone, Deserialize)]
+pub struct WindLoadsConfig {
+    /// wind loads pickle or parquet (`.parquet`) file
+    pub path: PathBuf,
+    /// time range `[t_min,t_max[` in seconds
+    pub range: Option<(f64, f64)>,
+    pub wind_speed_scaling: Option<WindSpeedScalingConfig>,
+    #[serde(default)]
+    pub remove_mean: bool,
+    pub looping: Option<LoopingConfig>,
+    /// raised cosine ramp duration in seconds
+    pub ramp_in: Option<f64>,
+    #[serde(default)]
+    pub transforms: Vec<TransformConfig>,
+    /// mappings of the wind loads to the FEM inputs, all the loads are selected if empty
+    #[serde(default)]
+    pub mappings: Vec<MappingConfig>,
+}
+/// Wind speed scaling of the CFD wind loads, see [`WindLoads::scale_wind_speed`](crate::WindLoads::scale_wind_speed)
+#[derive(Debug, Clone, Deserialize)]
+pub struct WindSpeedScalingConfig {
+    /// CFD wind speed in m/s
+    pub cfd_wind_speed: f64,
+    /// scaled wind speed in m/s
+    pub wind_speed: f64,
+}
+/// Wind loads repetition, see [`WindLoads::looping`](crate::WindLoads::looping)
+#[derive(Debug, Clone, Deserialize)]
+pub struct LoopingConfig {
+    /// duration in seconds
+    pub duration: f64,
+    /// cross-fading duration in seconds
+    pub crossfade: f64,
+}
+/// Coordinate transforms of the nodes of some wind loads
+#[derive(Debug, Clone, Deserialize)]
+pub struct TransformConfig {
+    #[serde(deserialize_with = "tag")]
+    pub source: Tags,
+    /// one transform per node
+    pub nodes: Vec<NodeTransformConfig>,
+}
+/// Coordinate transform of a node, see [`NodeTransform`]
+#[derive(Debug, Clone, Deserialize)]
+pub struct NodeTransformConfig {
+    pub rotation: Option<[[f64; 3]; 3]>,
+    pub offset: Option<[f64; 3]>,
+}
+impl From<&NodeTransformConfig> for NodeTransform {
+    fn from(config: &NodeTransformConfig) -> Self {
+        let transform = NodeTransform::new();
+        let transform = match config.rotation {
+            Some(rotation) => transform.rotation(rotation),
+            None => transform,
+        };
+        match config.offset {
+            Some(offset) => transform.offset(offset),
+            None => transform,
+        }
+    }
+}
+/// Mapping of CFD wind loads to a FEM input, see [`LoadsMapping`]
+#[derive(Debug, Clone, Deserialize)]
+pub struct MappingConfig {
+    /// CFD wind loads key
+    pub key: String,
+    #[serde(deserialize_with = "tag")]
+    pub target: Tags,
+    pub nodes: Option<Vec<usize>>,
+}
+impl From<&MappingConfig> for LoadsMapping {
+    fn from(config: &MappingConfig) -> Self {
+        let mapping = LoadsMapping::new(config.key.as_str(), config.target.clone());
+        match &config.nodes {
+            Some(nodes) => mapping.nodes(nodes.clone()),
+            None => mapping,
+        }
+    }
+}
+
+/// Controller configuration
+#[derive(Debug, Clone, Deserialize)]
+pub struct ControllerConfig {
+    /// component name
+    pub name: String,
+    /// update rate in Hz, the simulation sampling rate if `None`
+    pub rate: Option<f64>,
+    #[serde(flatten)]
+    pub kind: ControllerKind,
+}
+impl ControllerConfig {
+    /// Returns the update rate in Hz
+    pub fn sampling_rate(&self, sampling_rate: f64) -> f64 {
+        self.rate.unwrap_or(sampling_rate)
+    }
+    /// Returns the number of simulation time steps between updates
+    pub fn decimation(&self, sampling_rate: f64) -> Result<usize> {
+        if self
+            .rate
+            .is_some_and(|rate| !(rate.is_finite() && rate > 0f64))
+        {
+            return Err(ConfigError::Invalid(format!(
+                "the rate of {} must be greater than 0",
+                self.name
+            )));
+        }
+        let ratio = sampling_rate / self.sampling_rate(sampling_rate);
+        let decimation = ratio.round();
+        if decimation < 1f64 || (ratio - decimation).abs() > 1e-6 {
+            Err(ConfigError::Invalid(format!(
+                "the rate of {} must divide the sampling rate",
+                self.name
+            )))
+        } else {
+            Ok(decimation as usize)
+        }
+    }
+}
+/// Controller types and parameters
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ControllerKind {
+    /// PDR mount drives
+    MountDrives,
+    /// PDR mount controller
+    MountController,
+    /// M1 hardpoints load cells
+    M1HardpointsLoadCells,
+    /// M1 center of gravity controller
+    M1CgController,
+    /// [`Pid`](crate::controllers::linear::Pid) controller
+    Pid {
+        #[serde(deserialize_with = "tag")]
+        input: Tags,
+        #[serde(deserialize_with = "tag")]
+        output: Tags,
+        kp: f64,
+        #[serde(default)]
+        ki: f64,
+        #[serde(default)]
+        kd: f64,
+        /// output range `[min,max]`
+        saturation: Option<(f64, f64)>,
+        /// derivative filter time constant in seconds
+        derivative_filter: Option<f64>,
+    },
+    /// [`TransferFunction`](crate::controllers::linear::TransferFunction) with polynomials in $`z^{-1}`$
+    TransferFunction {
+        #[serde(deserialize_with = "tag")]
+        input: Tags,
+        #[serde(deserialize_with = "tag")]
+        output: Tags,
+        num: Vec<f64>,
+        den: Vec<f64>,
+    },
+    /// [`low_pass`](crate::controllers::linear::low_pass) filter
+    LowPass {
+        #[serde(deserialize_with = "tag")]
+        input: Tags,
+        #[serde(deserialize_with = "tag")]
+        output: Tags,
+        /// cutoff frequency in Hz
+        cutoff_frequency: f64,
+    },
+    /// [`notch`](crate::controllers::linear::notch) filter
+    Notch {
+        #[serde(deserialize_with = "tag")]
+        input: Tags,
+        #[serde(deserialize_with = "tag")]
+        output: Tags,
+        /// notch frequency in Hz
+        frequency: f64,
+        q: f64,
+    },
+    /// [`Delay`](crate::controllers::linear::Delay) of `n` updates
+    Delay {
+        #[serde(deserialize_with = "tag")]
+        input: Tags,
+        #[serde(deserialize_with = "tag")]
+        output: Tags,
+        n: usize,
+    },
+    /// [`SummingJunction`](crate::controllers::SummingJunction)
+    SummingJunction {
+        #[serde(deserialize_with = "tag")]
+        output: Tags,
+        inputs: Vec<GainConfig>,
+    },
+}
+impl ControllerKind {
+    /// Returns the controller type name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::MountDrives => "mount_drives",
+            Self::MountController => "mount_controller",
+            Self::M1HardpointsLoadCells => "m1_hardpoints_load_cells",
+            Self::M1CgController => "m1_cg_controller",
+            Self::Pid { .. } => "pid",
+            Self::TransferFunction { .. } => "transfer_function",
+            Self::LowPass { .. } => "low_pass",
+            Self::Notch { .. } => "notch",
+            Self::Delay { .. } => "delay",
+            Self::SummingJunction { .. } => "summing_junction",
+        }
+    }
+    /// Returns true for the controllers generated with Simulink
+    pub fn is_simulink(&self) -> bool {
+        matches!(
+            self,
+            Self::MountDrives
+                | Self::MountController
+                | Self::M1HardpointsLoadCells
+                | Self::M1CgController
+        )
+    }
+}
+/// Summing junction input
+#[derive(Debug, Clone, Deserialize)]
+pub struct GainConfig {
+    #[serde(deserialize_with = "tag")]
+    pub io: Tags,
+    #[serde(default = "unit_gain")]
+    pub gain: f64,
+}
+fn unit_gain() -> f64 {
+    1f64
+}
+
+/// Connections configuration
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ConnectionsConfig {
+    /// initial values of the delayed inputs and values of the constant inputs
+    #[serde(default)]
+    pub initial: Vec<InitialConfig>,
+}
+/// Initial value of an input
+#[derive(Debug, Clone, Deserialize)]
+pub struct InitialConfig {
+    #[serde(deserialize_with = "tag")]
+    pub io: Tags,
+    /// size of a zero initial value
+    pub size: Option<usize>,
+    pub value: Option<Vec<f64>>,
+}
+impl From<&InitialConfig> for IO<Vec<f64>> {
+    fn from(config: &InitialConfig) -> Self {
+        let value = config
+            .value
+            .clone()
+            .unwrap_or_else(|| vec![0f64; config.size.unwrap_or_default()]);
+        (&config.io, value).into()
+    }
+}
+
+/// Data logging configuration
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LoggingConfig {
+    #[serde(default)]
+    pub keys: Vec<KeyConfig>,
+    #[serde(default)]
+    pub statistics: Vec<StatisticsConfig>,
+    /// streaming log file
+    pub stream: Option<StreamConfig>,
+    /// pickle or parquet (`.parquet`) file of the time series of the keys
+    pub output: Option<PathBuf>,
+    /// JSON (`.json`) or pickle file of the statistics
+    pub statistics_output: Option<PathBuf>,
+}
+/// Logged key
+#[derive(Debug, Clone, Deserialize)]
+pub struct KeyConfig {
+    #[serde(deserialize_with = "tag")]
+    pub io: Tags,
+    #[serde(default = "unit_decimation")]
+    pub decimation: usize,
+}
+fn unit_decimation() -> usize {
+    1
+}
+/// Online statistics of a key, see [`OnlineStatistics`]
+#[derive(Debug, Clone, Deserialize)]
+pub struct StatisticsConfig {
+    #[serde(deserialize_with = "tag")]
+    pub io: Tags,
+    pub histogram: Option<HistogramConfig>,
+    /// PSD segments size
+    pub n_fft: Option<usize>,
+}
+impl From<&StatisticsConfig> for OnlineStatistics {
+    fn from(config: &StatisticsConfig) -> Self {
+        let options = OnlineStatistics::new();
+        let options = match &config.histogram {
+            Some(h) => options.histogram(h.n_bin, h.min, h.max),
+            None => options,
+        };
+        match config.n_fft {
+            Some(n_fft) => options.psd(n_fft),
+            None => options,
+        }
+    }
+}
+/// Histogram of the values in the range `[min,max)`
+#[derive(Debug, Clone, Deserialize)]
+pub struct HistogramConfig {
+    pub n_bin: usize,
+    pub min: f64,
+    pub max: f64,
+}
+/// Streaming log file, see [`streaming`](crate::telltale::streaming)
+#[derive(Debug, Clone, Deserialize)]
+pub struct StreamConfig {
+    pub path: PathBuf,
+    /// number of time steps between flushes
+    pub chunk_size: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        controllers::mount::pdr,
+        io::{io_name, jar},
+        model::Model,
+        telltale::{replay::Replay, TimeSeries},
+        DOSIOSError, DataLogging, IOTags, DOS,
+    };
+    use std::collections::BTreeMap;
+
+    /// FEM stand-in with the configured inputs and outputs
+    struct Fem {
+        inputs: Vec<Tags>,
+        outputs: Vec<Tags>,
+    }
+    impl Iterator for Fem {
+        type Item = ();
+        fn next(&mut self) -> Option<()> {
+            Some(())
+        }
+    }
+    impl IOTags for Fem {
+        fn outputs_tags(&self) -> Vec<Tags> {
+            self.outputs.clone()
+        }
+        fn inputs_tags(&self) -> Vec<Tags> {
+            self.inputs.clone()
+        }
+    }
+    impl DOS for Fem {
+        fn inputs(&mut self, _: Vec<IO<Vec<f64>>>) -> std::result::Result<&mut Self, DOSIOSError> {
+            Ok(self)
+        }
+        fn outputs(&mut self) -> Option<Vec<IO<Vec<f64>>>> {
+            Some(
+                self.outputs
+                    .iter()
+                    .map(|tag| (tag, vec![0f64]).into())
+                    .collect(),
+            )
+        }
+    }
+
+    #[test]
+    fn example_model() {
+        // the configuration of the module example
+        let config = SimulationConfig::from_toml(
+            r#"
+sampling_rate = 1000.0
+
+[fem]
+path = "data/modal_state_space_model_2ndOrder.pkl"
+proportional_damping = 0.02
+inputs = ["OSSAzDriveTorque", "OSSElDriveTorque", "OSSRotDriveTorque"]
+outputs = ["OSSAzEncoderAngle", "OSSElEncoderAngle", "OSSRotEncoderAngle", "OSSM1Lcl"]
+
+[wind_loads]
+path = "data/wind_loads.pkl"
+range = [100.0, 400.0]
+ramp_in = 1.0
+mappings = [
+    { key = "OSS_Truss_6F", target = "OSSTruss6F" },
+    { key = "OSS_M1_lcl_6F", target = "OSSM1Lcl6F" },
+]
+
+[[controllers]]
+name = "mount controller"
+kind = "mount_controller"
+rate = 1000.0
+
+[[controllers]]
+name = "mount drives"
+kind = "mount_drives"
+
+# the feedback loop is broken at the FEM inputs
+[connections]
+initial = [
+    { io = "OSSAzDriveTorque", size = 12 },
+    { io = "OSSElDriveTorque", size = 4 },
+    { io = "OSSRotDriveTorque", size = 4 },
+]
+
+[logging]
+keys = [{ io = "OSSM1Lcl" }, { io = "OSSAzEncoderAngle", decimation = 10 }]
+statistics = [{ io = "OSSM1Lcl", n_fft = 4096 }]
+output = "data/simulation.pkl"
+statistics_output = "data/statistics.json"
+"#,
+        )
+        .unwrap();
+        let fs = config.sampling_rate;
+        let mappings = &config.wind_loads.as_ref().unwrap().mappings;
+        let entries: BTreeMap<String, TimeSeries> = mappings
+            .iter()
+            .map(|x| (io_name(&x.target), vec![(0f64, vec![0f64; 6])]))
+            .collect();
+        let mut wind_loading = mappings
+            .iter()
+            .try_fold(Replay::new(fs, entries), |replay, x| {
+                replay.key(x.target.clone())
+            })
+            .unwrap();
+        let mut fem = Fem {
+            inputs: mappings
+                .iter()
+                .map(|x| x.target.clone())
+                .chain(config.fem.inputs.iter().cloned())
+                .collect(),
+            outputs: config.fem.outputs.clone(),
+        };
+        let mut mount_controller = pdr::controller::Controller::new();
+        let mut mount_drives = pdr::drives::Controller::new();
+        let mut data = config
+            .logging
+            .keys
+            .iter()
+            .fold(DataLogging::new().sampling_rate(fs), |data, key| {
+                data.decimated_key(key.io.clone(), key.decimation)
+            });
+        data = config
+            .logging
+            .statistics
+            .iter()
+            .fold(data, |data, x| data.statistics(x.io.clone(), x.into()));
+        let mut data = data.build();
+        let (ctrl, drives) = (&config.controllers[0], &config.controllers[1]);
+        assert!(matches!(ctrl.kind, ControllerKind::MountController));
+        assert!(matches!(drives.kind, ControllerKind::MountDrives));
+        let model = Model::new()
+            .source("wind loads", &mut wind_loading)
+            .component("FEM", &mut fem)
+            .decimated_component(
+                &ctrl.name,
+                &mut mount_controller,
+                ctrl.decimation(fs).unwrap(),
+            )
+            .decimated_component(
+                &drives.name,
+                &mut mount_drives,
+                drives.decimation(fs).unwrap(),
+            );
+        let simulation = config
+            .connections
+            .initial
+            .iter()
+            .fold(model, |model, initial| model.initial(initial.into()))
+            .component("data logging", &mut data)
+            .build()
+            .unwrap();
+        let report = simulation.report();
+        assert_eq!(
+            report.order,
+            vec![
+                "wind loads",
+                "FEM",
+                "mount controller",
+                "mount drives",
+                "data logging"
+            ]
+        );
+        let delayed: Vec<&str> = report
+            .delayed
+            .iter()
+            .map(|(c, tag, _)| {
+                assert_eq!(c, "FEM");
+                tag.as_str()
+            })
+            .collect();
+        assert_eq!(
+            delayed,
+            vec!["OSSAzDriveTorque", "OSSElDriveTorque", "OSSRotDriveTorque"]
+        );
+        assert!(report.unconnected.is_empty());
+    }
+
+    #[test]
+    fn toml_yaml_config() {
+        let toml_config = SimulationConfig::from_toml(
+            r#"
+sampling_rate = 1000.0
+
+[fem]
+path = "fem.pkl"
+proportional_damping = 0.02
+eigen_frequencies = [[0, 0.0], [1, 0.0]]
+inputs = ["OSSAzDriveTorque", "M1CGFM"]
+outputs = ["OSSM1Lcl", "OSSHardpointD"]
+
+[wind_loads]
+path = "wind_loads.pkl"
+range = [100.0, 400.0]
+wind_speed_scaling = { cfd_wind_speed = 7.0, wind_speed = 10.0 }
+transforms = [{ source = "OSSTopEnd6F", nodes = [{ offset = [0.0, 0.0, 0.25] }] }]
+mappings = [{ key = "OSS_M1_lcl_6F", target = "OSSM1Lcl6F", nodes = [6] }]
+
+[[controllers]]
+name = "hardpoints"
+kind = "m1_hardpoints_load_cells"
+
+[[controllers]]
+name = "pid"
+kind = "pid"
+rate = 100.0
+input = "OSSHardpointD"
+output = "M1HPCmd"
+kp = 1e3
+saturation = [-1e3, 1e3]
+
+[[controllers]]
+name = "cells"
+kind = "summing_junction"
+output = "OSSCellLcl6F"
+inputs = [{ io = "M1CGFM", gain = -1.0 }]
+
+[connections]
+initial = [{ io = "M1HPCmd", size = 42 }, { io = "MountCmd", value = [1.0, 2.0, 3.0] }]
+
+[logging]
+keys = [{ io = "OSSM1Lcl" }, { io = "OSSHardpointD", decimation = 10 }]
+statistics = [{ io = "OSSM1Lcl", n_fft = 64, histogram = { n_bin = 10, min = -1.0, max = 1.0 } }]
+output = "data.pkl"
+"#,
+        )
+        .unwrap();
+        let yaml_config = SimulationConfig::from_yaml(
+            r#"
+sampling_rate: 1000.0
+duration: 2.0
+fem:
+  path: fem.pkl
+  outputs: [OSSM1Lcl]
+controllers:
+  - name: pid
+    kind: pid
+    rate: 100.0
+    input: OSSHardpointD
+    output: M1HPCmd
+    kp: 1000.0
+logging:
+  keys:
+    - io: OSSM1Lcl
+"#,
+        )
+        .unwrap();
+        assert_eq!(yaml_config.n_step(), Some(2000));
+        assert!(toml_config.fem.inputs[1] == jar::M1CGFM::new::<()>());
+        assert_eq!(toml_config.fem.eigen_frequencies, vec![(0, 0.), (1, 0.)]);
+        let wind_loads = toml_config.wind_loads.as_ref().unwrap();
+        let mapping = LoadsMapping::from(&wind_loads.mappings[0]);
+        assert_eq!(mapping.nodes, Some(vec![6]));
+        let transform = NodeTransform::from(&wind_loads.transforms[0].nodes[0]);
+        assert_eq!(transform.offset, [0., 0., 0.25]);
+        for config in [&toml_config, &yaml_config].iter() {
+            let pid = config.controllers.iter().find(|c| c.name == "pid").unwrap();
+            assert_eq!(pid.decimation(config.sampling_rate).unwrap(), 10);
+            assert!(matches!(pid.kind, ControllerKind::Pid { kp, .. } if kp == 1e3));
+        }
+        assert!(toml_config.controllers[0].kind.is_simulink());
+        assert!(matches!(
+            &toml_config.controllers[2].kind,
+            ControllerKind::SummingJunction { inputs, .. } if inputs[0].gain == -1.
+        ));
+        let initial: Vec<IO<Vec<f64>>> = toml_config
+            .connections
+            .initial
+            .iter()
+            .map(|x| x.into())
+            .collect();
+        assert_eq!(Option::<Vec<f64>>::from(&initial[0]), Some(vec![0.; 42]));
+        assert_eq!(
+            Option::<Vec<f64>>::from(&initial[1]),
+            Some(vec![1., 2., 3.])
+        );
+        assert_eq!(toml_config.logging.keys[1].decimation, 10);
+        // unknown IO, missing duration and rate not dividing the sampling rate
+        assert!(SimulationConfig::from_yaml(
+            "sampling_rate: 1.0\nduration: 1.0\nfem: {path: fem.pkl, outputs: [M1Foo]}"
+        )
+        .is_err());
+        assert!(SimulationConfig::from_yaml(
+            "sampling_rate: 1.0\nfem: {path: fem.pkl, outputs: []}"
+        )
+        .is_err());
+        assert!(SimulationConfig::from_yaml(
+            "sampling_rate: 1000.0\nduration: 1.0\nfem: {path: fem.pkl, outputs: []}\ncontrollers: [{name: d, kind: delay, rate: 300.0, input: M1HPLC, output: M1CGFM, n: 1}]"
+        )
+        .is_err());
+        // invalid rates, decimation, chunk size, PSD and histogram
+        for invalid in [
+            "controllers: [{name: d, kind: delay, rate: 0.0, input: M1HPLC, output: M1CGFM, n: 1}]",
+            "controllers: [{name: d, kind: delay, rate: .nan, input: M1HPLC, output: M1CGFM, n: 1}]",
+            "logging: {keys: [{io: OSSM1Lcl, decimation: 0}]}",
+            "logging: {stream: {path: log.bin, chunk_size: 0}}",
+            "logging: {statistics: [{io: OSSM1Lcl, n_fft: 1}]}",
+            "logging: {statistics: [{io: OSSM1Lcl, histogram: {n_bin: 0, min: 0.0, max: 1.0}}]}",
+            "logging: {statistics: [{io: OSSM1Lcl, histogram: {n_bin: 4, min: 1.0, max: 1.0}}]}",
+        ]
+        .iter()
+        {
+            assert!(matches!(
+                SimulationConfig::from_yaml(&format!(
+                    "sampling_rate: 1000.0\nduration: 1.0\nfem: {{path: fem.pkl, outputs: []}}\n{}",
+                    invalid
+                )),
+                Err(ConfigError::Invalid(_))
+            ));
+        }
+    }
+}
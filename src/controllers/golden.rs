@@ -0,0 +1,134 @@
+//! Golden trajectories of the controllers
+//!
+//! A golden trajectory is a sequence of controller inputs together with the controller outputs recorded from a reference run.
+//! The trajectories are saved in JSON files and they are used as regression snapshots: replaying a trajectory detects any change of the controller behavior since it was recorded.
+//!
+//! The trajectories in `tests/golden` are self-recorded from the Rust wrappers of the compiled Simulink controllers, not from the Simulink models,
+//! so they do not verify that the controllers match their Simulink references.
+//!
+//! The inputs and outputs are given in the order of the controller [`inputs_tags`](crate::IOTags::inputs_tags) and [`outputs_tags`](crate::IOTags::outputs_tags), respectively.
+//!
+//! # Example
+//! Recording and then replaying a golden trajectory of the M1 center of gravity controller:
+//! ```no_run
+//! use dos::controllers::{golden::Golden, m1};
+//!
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let inputs: Vec<Vec<Vec<f64>>> = (0..10).map(|k| vec![vec![k as f64 * 1e-3; 42]]).collect();
+//!     let mut m1_ctrl = m1::cg_controller::Controller::new();
+//!     Golden::record("M1OFL_Control", &mut m1_ctrl, inputs)?.to_json("M1OFL_Control.json")?;
+//!     let mut m1_ctrl = m1::cg_controller::Controller::new();
+//!     Golden::from_json("M1OFL_Control.json")?.compare(&mut m1_ctrl, 1e-9)?;
+//!     Ok(())
+//! }
+//! ```
+
+use crate::{DOSError, IOTags, DOS, IO};
+use serde::{Deserialize, Serialize};
+use std::{fs::File, io::BufReader, path::Path};
+
+type Result<T> = std::result::Result<T, DOSError>;
+
+/// Controller golden trajectory
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Golden {
+    /// Controller name
+    pub controller: String,
+    /// Inputs time series: `[step][input][value]`
+    pub inputs: Vec<Vec<Vec<f64>>>,
+    /// Outputs time series: `[step][output][value]`
+    pub outputs: Vec<Vec<Vec<f64>>>,
+}
+impl Golden {
+    /// Runs the `controller` with the `inputs` time series and records the outputs
+    pub fn record<T, S>(controller_name: S, controller: &mut T, inputs: Vec<Vec<Vec<f64>>>) -> Result<Self>
+    where
+        T: DOS + IOTags + Iterator,
+        S: Into<String>,
+    {
+        let outputs = inputs
+            .iter()
+            .map(|u| Self::step(controller, u))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            controller: controller_name.into(),
+            inputs,
+            outputs,
+        })
+    }
+    /// Reads a golden trajectory from a JSON file
+    pub fn from_json<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let f = File::open(path)?;
+        Ok(serde_json::from_reader(BufReader::new(f))?)
+    }
+    /// Writes the golden trajectory to a JSON file
+    pub fn to_json<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let f = File::create(path)?;
+        Ok(serde_json::to_writer(f, self)?)
+    }
+    /// Runs the `controller` with the golden trajectory inputs and compares the outputs to the golden trajectory outputs
+    ///
+    /// The comparison fails at the 1st output sample $`y`$ that departs from the golden sample $`y_g`$ by more than $`t\max(1,|y_g|)`$ where $`t`$ is the `tolerance`
+    pub fn compare<T>(&self, controller: &mut T, tolerance: f64) -> Result<()>
+    where
+        T: DOS + IOTags + Iterator,
+    {
+        let tags = controller.outputs_tags();
+        for (k, (u, y_golden)) in self.inputs.iter().zip(self.outputs.iter()).enumerate() {
+            let y = Self::step(controller, u)?;
+            if y.len() != y_golden.len() {
+                return Err(format!(
+                    "{} step #{}: expected {} outputs, found {}",
+                    self.controller,
+                    k,
+                    y_golden.len(),
+                    y.len()
+                )
+                .into());
+            }
+            for ((tag, y), y_golden) in tags.iter().zip(y.iter()).zip(y_golden.iter()) {
+                if y.len() != y_golden.len() {
+                    return Err(format!(
+                        "{} step #{} {:?}: expected {} values, found {}",
+                        self.controller,
+                        k,
+                        tag,
+                        y_golden.len(),
+                        y.len()
+                    )
+                    .into());
+                }
+                if let Some((i, (y, y_golden))) = y
+                    .iter()
+                    .zip(y_golden.iter())
+                    .enumerate()
+                    .find(|(_, (y, y_golden))| (*y - *y_golden).abs() > tolerance * y_golden.abs().max(1f64))
+                {
+                    return Err(format!(
+                        "{} step #{} {:?}[{}]: expected {}, found {}",
+                        self.controller, k, tag, i, y_golden, y
+                    )
+                    .into());
+                }
+            }
+        }
+        Ok(())
+    }
+    fn step<T>(controller: &mut T, u: &[Vec<f64>]) -> Result<Vec<Vec<f64>>>
+    where
+        T: DOS + IOTags + Iterator,
+    {
+        let inputs: Vec<IO<Vec<f64>>> = controller
+            .inputs_tags()
+            .iter()
+            .zip(u.iter())
+            .map(|(tag, u)| IO::<Vec<f64>>::from((tag, u.clone())))
+            .collect();
+        controller
+            .in_step_out(inputs)?
+            .ok_or(DOSError::Outputs)?
+            .iter()
+            .map(|y| Option::<Vec<f64>>::from(y).ok_or(DOSError::Outputs))
+            .collect()
+    }
+}
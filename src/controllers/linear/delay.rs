@@ -0,0 +1,44 @@
+//! Pure delay
+
+use super::Solver;
+use std::collections::VecDeque;
+
+/// Pure delay of `n` samples
+#[derive(Debug, Clone)]
+pub struct Delay {
+    n: usize,
+    buffer: VecDeque<Vec<f64>>,
+}
+impl Delay {
+    /// Creates a new delay of `n` samples
+    pub fn new(n: usize) -> Self {
+        Self {
+            n,
+            buffer: VecDeque::with_capacity(n + 1),
+        }
+    }
+}
+impl Solver for Delay {
+    fn solve(&mut self, u: &[f64]) -> Vec<f64> {
+        if self.buffer.front().is_none_or(|x| x.len() != u.len()) {
+            self.buffer = vec![vec![0f64; u.len()]; self.n].into();
+        }
+        self.buffer.push_back(u.to_vec());
+        self.buffer.pop_front().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delay_samples() {
+        let mut delay = Delay::new(2);
+        let y: Vec<f64> = (1..6).map(|k| delay.solve(&[k as f64])[0]).collect();
+        assert_eq!(y, vec![0., 0., 1., 2., 3.]);
+        // a change of input size restarts the delay line
+        assert_eq!(delay.solve(&[1., 1.]), vec![0., 0.]);
+        assert_eq!(Delay::new(0).solve(&[1.]), vec![1.]);
+    }
+}
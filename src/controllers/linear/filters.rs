@@ -0,0 +1,50 @@
+//! Discrete filters
+//!
+//! The filters are [`TransferFunction`]s designed from the cut-off or center frequency in Hz and from the sampling frequency in Hz.
+
+use super::TransferFunction;
+use std::f64::consts::PI;
+
+/// 1st order low-pass filter
+///
+/// The filter is $`y[k] = y[k-1] + \alpha(u[k]-y[k-1])`$ with $`\alpha=1-\exp(-2\pi f_c/f_s)`$
+pub fn low_pass(cutoff_frequency: f64, sampling_frequency: f64) -> TransferFunction {
+    let alpha = 1f64 - (-2. * PI * cutoff_frequency / sampling_frequency).exp();
+    TransferFunction::new(vec![alpha], vec![1., alpha - 1.])
+}
+/// 2nd order notch filter
+///
+/// The notch is centered on the frequency `frequency` with the quality factor `q`
+pub fn notch(frequency: f64, q: f64, sampling_frequency: f64) -> TransferFunction {
+    let w0 = 2. * PI * frequency / sampling_frequency;
+    let alpha = 0.5 * w0.sin() / q;
+    let c = -2. * w0.cos();
+    TransferFunction::new(vec![1., c, 1.], vec![1. + alpha, c, 1. - alpha])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controllers::linear::Solver;
+
+    /// Returns the steady state amplitude of the filter response to a sinusoid at `f` Hz
+    fn amplitude(mut tf: TransferFunction, f: f64, fs: f64) -> f64 {
+        (0..20_000)
+            .map(|k| tf.solve(&[(2. * PI * f * k as f64 / fs).sin()])[0])
+            .skip(10_000)
+            .fold(0f64, |a, y| a.max(y.abs()))
+    }
+
+    #[test]
+    fn filters_response() {
+        let fs = 1e3;
+        let lp = low_pass(10., fs);
+        assert!((lp.dc_gain() - 1.).abs() < 1e-12);
+        assert!(amplitude(lp.clone(), 1., fs) > 0.99);
+        assert!(amplitude(lp, 200., fs) < 0.1);
+        let n = notch(50., 2., fs);
+        assert!((n.dc_gain() - 1.).abs() < 1e-12);
+        assert!(amplitude(n.clone(), 50., fs) < 1e-3);
+        assert!(amplitude(n, 200., fs) > 0.9);
+    }
+}
@@ -0,0 +1,157 @@
+//! Linear time invariant discrete controllers
+//!
+//! This module provides simple discrete linear time invariant (LTI) building blocks written in Rust:
+//!  - [`TransferFunction`] for discrete transfer functions given by their numerator and denominator polynomials in $`z^{-1}`$,
+//!  - [`StateSpace`] for discrete state space models,
+//!  - [`Pid`] for PID controllers with anti-windup,
+//!  - [`low_pass`] and [`notch`] filters,
+//!  - [`Delay`] for pure delays.
+//!
+//! Each building block implements the [`Solver`] trait and is turned into a DOS component with [`Solver::block`] that sets the block input and output [`IO`] tags.
+//! A block applies the same single input single output transfer function to each element of the input vector, except [`StateSpace`] that is multiple inputs multiple outputs.
+//!
+//! # Example
+//! A PID controller between the M1 hardpoints displacements and the M1 hardpoints commands:
+//! ```no_run
+//! use dos::{controllers::linear::{Pid, Solver}, io::jar, DOS};
+//!
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let mut pid = Pid::new(1e3, 0.5, 10., 1e-3)
+//!         .saturation(-1e3, 1e3)
+//!         .block(jar::OSSHardpointD::new(), jar::M1HPCmd::new());
+//!     let y = pid.in_step_out(vec![jar::OSSHardpointD::with(vec![1e-6; 84])])?;
+//!     Ok(())
+//! }
+//! ```
+
+use crate::{io::Tags, DOSIOSError, IOTags, DOS, IO};
+
+pub mod delay;
+#[doc(inline)]
+pub use delay::Delay;
+pub mod filters;
+#[doc(inline)]
+pub use filters::{low_pass, notch};
+pub mod pid;
+#[doc(inline)]
+pub use pid::Pid;
+pub mod state_space;
+#[doc(inline)]
+pub use state_space::StateSpace;
+pub mod transfer_function;
+#[doc(inline)]
+pub use transfer_function::TransferFunction;
+
+/// Discrete linear system interface
+pub trait Solver {
+    /// Updates the system with the input `u` and returns the system output
+    fn solve(&mut self, u: &[f64]) -> Vec<f64>;
+    /// Returns the number of inputs if the system expects a fixed number of inputs
+    fn n_inputs(&self) -> Option<usize> {
+        None
+    }
+    /// Creates a DOS component from the linear system with the given input and output tags
+    fn block(self, input: Tags, output: Tags) -> LinearBlock<Self>
+    where
+        Self: Sized,
+    {
+        LinearBlock {
+            system: self,
+            u_tag: input,
+            y_tag: output,
+            u: vec![],
+            y: vec![],
+        }
+    }
+}
+
+/// DOS component for a linear system
+///
+/// The component takes a single input and returns a single output
+#[derive(Debug, Clone)]
+pub struct LinearBlock<T> {
+    /// linear system
+    pub system: T,
+    u_tag: Tags,
+    y_tag: Tags,
+    u: Vec<f64>,
+    y: Vec<f64>,
+}
+impl<T: Solver> Iterator for LinearBlock<T> {
+    type Item = ();
+    fn next(&mut self) -> Option<Self::Item> {
+        self.y = self.system.solve(&self.u);
+        Some(())
+    }
+}
+impl<T: Solver> IOTags for LinearBlock<T> {
+    fn outputs_tags(&self) -> Vec<Tags> {
+        vec![self.y_tag.clone()]
+    }
+    fn inputs_tags(&self) -> Vec<Tags> {
+        vec![self.u_tag.clone()]
+    }
+}
+impl<T: Solver> DOS for LinearBlock<T> {
+    fn inputs(&mut self, data: Vec<IO<Vec<f64>>>) -> Result<&mut Self, DOSIOSError> {
+        match data.iter().find(|&x| self.u_tag == *x) {
+            Some(io) => {
+                let u = Option::<Vec<f64>>::from(io).ok_or_else(|| {
+                    DOSIOSError::Inputs(format!("{:?} has no data", self.u_tag).into())
+                })?;
+                if let Some(n) = self.system.n_inputs() {
+                    if u.len() != n {
+                        return Err(DOSIOSError::Inputs(
+                            format!(
+                                "linear block input {:?} expects {} values, found {}",
+                                self.u_tag,
+                                n,
+                                u.len()
+                            )
+                            .into(),
+                        ));
+                    }
+                }
+                self.u = u;
+                Ok(self)
+            }
+            None => Err(DOSIOSError::Inputs(
+                format!("{:?} not found in linear block inputs", self.u_tag).into(),
+            )),
+        }
+    }
+    fn outputs(&mut self) -> Option<Vec<IO<Vec<f64>>>> {
+        Some(vec![IO::<Vec<f64>>::from((&self.y_tag, self.y.clone()))])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::jar;
+
+    #[test]
+    fn linear_block_wiring() {
+        let mut block = Delay::new(1).block(jar::OSSHardpointD::new(), jar::M1HPCmd::new());
+        assert!(block.inputs_tags()[0] == jar::OSSHardpointD::new::<()>());
+        assert!(block.outputs_tags()[0] == jar::M1HPCmd::new::<()>());
+        assert!(block.inputs(vec![jar::M1HPLC::with(vec![1.])]).is_err());
+        let y: Vec<_> = (1..3)
+            .map(|k| {
+                let y = block
+                    .in_step_out(vec![jar::OSSHardpointD::with(vec![k as f64; 2])])
+                    .unwrap()
+                    .unwrap();
+                assert!(y[0] == jar::M1HPCmd::new::<()>());
+                Option::<Vec<f64>>::from(&y[0]).unwrap()
+            })
+            .collect();
+        assert_eq!(y, vec![vec![0.; 2], vec![1.; 2]]);
+        // a state space model with 2 inputs rejects 3 inputs
+        let mut ss = StateSpace::from_row_slices(1, 2, 1, &[0.], &[1., 1.], &[1.], &[0., 0.])
+            .block(jar::OSSHardpointD::new(), jar::M1HPCmd::new());
+        assert!(ss
+            .inputs(vec![jar::OSSHardpointD::with(vec![1.; 3])])
+            .is_err());
+    }
+}
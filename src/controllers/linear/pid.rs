@@ -0,0 +1,117 @@
+//! PID controller
+//!
+//! The PID controller output is
+//! $$
+//! y\[k\] = k_p e\[k\] + I\[k\] + D\[k\]
+//! $$
+//! with the error $`e`$ as input and where
+//! $$
+//! I\[k\] = I[k-1] + k_i\tau e\[k\]
+//! $$
+//! $$
+//! D\[k\] = {T_f \over T_f + \tau} D[k-1] + {k_d \over T_f + \tau} (e\[k\] - e[k-1])
+//! $$
+//! $`\tau`$ is the sampling time and $`T_f`$ is the time constant of the derivative low-pass filter.
+//!
+//! When the output saturates, the integrator is frozen as long as the error drives the output further into saturation (conditional integration anti-windup).
+
+use super::Solver;
+
+/// PID controller with anti-windup
+#[derive(Debug, Clone)]
+pub struct Pid {
+    kp: f64,
+    ki: f64,
+    kd: f64,
+    tau: f64,
+    tf: f64,
+    saturation: Option<(f64, f64)>,
+    integral: Vec<f64>,
+    derivative: Vec<f64>,
+    error: Vec<f64>,
+}
+impl Pid {
+    /// Creates a new PID controller from the proportional, integral and derivative gains and the sampling time $`\tau`$
+    pub fn new(kp: f64, ki: f64, kd: f64, tau: f64) -> Self {
+        assert!(tau > 0f64, "the sampling time must be greater than 0");
+        Self {
+            kp,
+            ki,
+            kd,
+            tau,
+            tf: 0f64,
+            saturation: None,
+            integral: vec![],
+            derivative: vec![],
+            error: vec![],
+        }
+    }
+    /// Sets the output saturation limits
+    pub fn saturation(self, min: f64, max: f64) -> Self {
+        assert!(min < max, "the lower limit must be less than the upper limit");
+        Self {
+            saturation: Some((min, max)),
+            ..self
+        }
+    }
+    /// Sets the time constant $`T_f`$ of the derivative low-pass filter
+    pub fn derivative_filter(self, tf: f64) -> Self {
+        Self { tf, ..self }
+    }
+    /// Resets the controller states to 0
+    pub fn reset(&mut self) {
+        self.integral.clear();
+        self.derivative.clear();
+        self.error.clear();
+    }
+}
+impl Solver for Pid {
+    fn solve(&mut self, e: &[f64]) -> Vec<f64> {
+        let n = e.len();
+        if self.integral.len() != n {
+            self.integral = vec![0f64; n];
+            self.derivative = vec![0f64; n];
+            self.error = vec![0f64; n];
+        }
+        let (kp, ki, kd, tau, tf) = (self.kp, self.ki, self.kd, self.tau, self.tf);
+        let saturation = self.saturation;
+        e.iter()
+            .zip(self.integral.iter_mut())
+            .zip(self.derivative.iter_mut())
+            .zip(self.error.iter_mut())
+            .map(|(((e, i), d), e_past)| {
+                *d = (tf * *d + kd * (e - *e_past)) / (tf + tau);
+                *e_past = *e;
+                let i_next = *i + ki * tau * e;
+                let y = kp * e + i_next + *d;
+                match saturation {
+                    Some((min, max)) => {
+                        let winding_up = (y > max && *e > 0f64) || (y < min && *e < 0f64);
+                        if !winding_up {
+                            *i = i_next;
+                        }
+                        (kp * e + *i + *d).max(min).min(max)
+                    }
+                    None => {
+                        *i = i_next;
+                        y
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pid_anti_windup() {
+        let mut pid = Pid::new(0., 1., 0., 1.).saturation(-2., 2.);
+        let y: Vec<f64> = (0..5).map(|_| pid.solve(&[1.])[0]).collect();
+        assert_eq!(y, vec![1., 2., 2., 2., 2.]);
+        // the integrator is not wound up and the output leaves the saturation right away
+        assert_eq!(pid.solve(&[-1.])[0], 1.);
+    }
+}
@@ -0,0 +1,112 @@
+//! Discrete state space model
+//!
+//! $$
+//! x[k+1] = A x\[k\] + B u\[k\]
+//! $$
+//! $$
+//! y\[k\] = C x\[k\] + D u\[k\]
+//! $$
+
+use super::Solver;
+use nalgebra as na;
+
+/// Discrete state space model
+#[derive(Debug, Clone)]
+pub struct StateSpace {
+    a: na::DMatrix<f64>,
+    b: na::DMatrix<f64>,
+    c: na::DMatrix<f64>,
+    d: na::DMatrix<f64>,
+    x: na::DVector<f64>,
+}
+impl StateSpace {
+    /// Creates a new state space model from the matrices $`A`$, $`B`$, $`C`$ and $`D`$
+    pub fn new(
+        a: na::DMatrix<f64>,
+        b: na::DMatrix<f64>,
+        c: na::DMatrix<f64>,
+        d: na::DMatrix<f64>,
+    ) -> Self {
+        let n = a.nrows();
+        assert!(a.is_square(), "A must be a square matrix");
+        assert_eq!(b.nrows(), n, "A and B must have the same number of rows");
+        assert_eq!(c.ncols(), n, "C must have as many columns as A");
+        assert_eq!(
+            d.shape(),
+            (c.nrows(), b.ncols()),
+            "D must have as many rows as C and as many columns as B"
+        );
+        Self {
+            a,
+            b,
+            c,
+            d,
+            x: na::DVector::zeros(n),
+        }
+    }
+    /// Creates a new state space model from the row-major matrices $`A`$, $`B`$, $`C`$ and $`D`$ given the number of states, inputs and outputs
+    pub fn from_row_slices(
+        n_state: usize,
+        n_input: usize,
+        n_output: usize,
+        a: &[f64],
+        b: &[f64],
+        c: &[f64],
+        d: &[f64],
+    ) -> Self {
+        Self::new(
+            na::DMatrix::from_row_slice(n_state, n_state, a),
+            na::DMatrix::from_row_slice(n_state, n_input, b),
+            na::DMatrix::from_row_slice(n_output, n_state, c),
+            na::DMatrix::from_row_slice(n_output, n_input, d),
+        )
+    }
+    /// Returns the state vector
+    pub fn state(&self) -> &[f64] {
+        self.x.as_slice()
+    }
+    /// Resets the state vector to 0
+    pub fn reset(&mut self) {
+        self.x.fill(0f64);
+    }
+}
+impl Solver for StateSpace {
+    /// Updates the system with the input `u`
+    ///
+    /// # Panics
+    /// If the number of inputs does not match the number of columns of $`B`$, see [`Solver::n_inputs`]
+    fn solve(&mut self, u: &[f64]) -> Vec<f64> {
+        assert_eq!(
+            u.len(),
+            self.b.ncols(),
+            "expected {} inputs, found {}",
+            self.b.ncols(),
+            u.len()
+        );
+        let u = na::DVector::from_column_slice(u);
+        let y = &self.c * &self.x + &self.d * &u;
+        self.x = &self.a * &self.x + &self.b * u;
+        y.as_slice().to_vec()
+    }
+    fn n_inputs(&self) -> Option<usize> {
+        Some(self.b.ncols())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn state_space_accumulator() {
+        // x[k+1] = x[k] + u0[k] + u1[k], y[k] = [x[k], u1[k]]
+        let mut ss =
+            StateSpace::from_row_slices(1, 2, 2, &[1.], &[1., 1.], &[1., 0.], &[0., 0., 0., 1.]);
+        assert_eq!(ss.n_inputs(), Some(2));
+        let y: Vec<Vec<f64>> = (0..3).map(|_| ss.solve(&[1., 2.])).collect();
+        assert_eq!(y, vec![vec![0., 2.], vec![3., 2.], vec![6., 2.]]);
+        assert_eq!(ss.state(), &[9.]);
+        ss.reset();
+        assert_eq!(ss.solve(&[0., 0.]), vec![0., 0.]);
+    }
+}
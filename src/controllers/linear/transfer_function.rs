@@ -0,0 +1,88 @@
+//! Discrete transfer function
+//!
+//! A discrete transfer function is given by
+//! $$
+//! H(z) = {b_0 + b_1z^{-1} + \dots + b_nz^{-n} \over a_0 + a_1z^{-1} + \dots + a_mz^{-m}}
+//! $$
+//! and it is implemented as a transposed direct form II filter.
+
+use super::Solver;
+
+/// Discrete transfer function
+#[derive(Debug, Clone)]
+pub struct TransferFunction {
+    num: Vec<f64>,
+    den: Vec<f64>,
+    z: Vec<Vec<f64>>,
+}
+impl TransferFunction {
+    /// Creates a new transfer function from the numerator and denominator coefficients in increasing powers of $`z^{-1}`$
+    ///
+    /// The coefficients are normalized by the 1st coefficient of the denominator
+    pub fn new(num: Vec<f64>, den: Vec<f64>) -> Self {
+        assert!(
+            den.first().is_some_and(|a0| *a0 != 0f64),
+            "the 1st denominator coefficient must be non zero"
+        );
+        let a0 = den[0];
+        let n = num.len().max(den.len());
+        let mut num: Vec<f64> = num.into_iter().map(|b| b / a0).collect();
+        num.resize(n, 0f64);
+        let mut den: Vec<f64> = den.into_iter().map(|a| a / a0).collect();
+        den.resize(n, 0f64);
+        Self {
+            num,
+            den,
+            z: vec![],
+        }
+    }
+    /// Returns the numerator coefficients
+    pub fn num(&self) -> &[f64] {
+        &self.num
+    }
+    /// Returns the denominator coefficients
+    pub fn den(&self) -> &[f64] {
+        &self.den
+    }
+    /// Returns the transfer function static gain
+    pub fn dc_gain(&self) -> f64 {
+        self.num.iter().sum::<f64>() / self.den.iter().sum::<f64>()
+    }
+    /// Resets the transfer function states to 0
+    pub fn reset(&mut self) {
+        self.z.clear();
+    }
+}
+impl Solver for TransferFunction {
+    fn solve(&mut self, u: &[f64]) -> Vec<f64> {
+        let n = self.num.len();
+        if self.z.len() != u.len() {
+            self.z = vec![vec![0f64; n]; u.len()];
+        }
+        let (num, den) = (&self.num, &self.den);
+        u.iter()
+            .zip(self.z.iter_mut())
+            .map(|(u, z)| {
+                let y = num[0] * u + z[0];
+                for k in 1..n {
+                    z[k - 1] = num[k] * u - den[k] * y + z[k];
+                }
+                y
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transfer_function_step() {
+        // y[k] = 0.5u[k] + 0.5y[k-1]
+        let mut tf = TransferFunction::new(vec![0.5], vec![1., -0.5]);
+        let y: Vec<f64> = (0..4).map(|_| tf.solve(&[1., 2.])[1]).collect();
+        assert_eq!(y, vec![1., 1.5, 1.75, 1.875]);
+        assert_eq!(tf.dc_gain(), 1.);
+    }
+}
@@ -0,0 +1,45 @@
+/*
+ * File: M1OFL_Control_state.c
+ *
+ * Snapshot and restore of the 'M1OFL_Control' model states.
+ *
+ * The block signals, block states, external inputs and outputs and the
+ * real-time model object are copied to and from a contiguous byte buffer
+ * of size M1OFL_Control_state_size().
+ */
+
+#include <string.h>
+#include "M1OFL_Control.h"
+
+/* Size in bytes of the model state buffer */
+size_t M1OFL_Control_state_size(void)
+{
+  return sizeof(DW_M1OFL_Control_T)
+         + sizeof(ExtU_M1OFL_Control_T)
+         + sizeof(ExtY_M1OFL_Control_T)
+         + sizeof(RT_MODEL_M1OFL_Control_T);
+}
+
+/* Copies the model states into buffer */
+void M1OFL_Control_state_get(unsigned char *buffer)
+{
+  (void) memcpy(buffer, (void *)&M1OFL_Control_DW, sizeof(DW_M1OFL_Control_T));
+  buffer += sizeof(DW_M1OFL_Control_T);
+  (void) memcpy(buffer, (void *)&M1OFL_Control_U, sizeof(ExtU_M1OFL_Control_T));
+  buffer += sizeof(ExtU_M1OFL_Control_T);
+  (void) memcpy(buffer, (void *)&M1OFL_Control_Y, sizeof(ExtY_M1OFL_Control_T));
+  buffer += sizeof(ExtY_M1OFL_Control_T);
+  (void) memcpy(buffer, (void *)M1OFL_Control_M, sizeof(RT_MODEL_M1OFL_Control_T));
+}
+
+/* Copies buffer into the model states */
+void M1OFL_Control_state_set(const unsigned char *buffer)
+{
+  (void) memcpy((void *)&M1OFL_Control_DW, buffer, sizeof(DW_M1OFL_Control_T));
+  buffer += sizeof(DW_M1OFL_Control_T);
+  (void) memcpy((void *)&M1OFL_Control_U, buffer, sizeof(ExtU_M1OFL_Control_T));
+  buffer += sizeof(ExtU_M1OFL_Control_T);
+  (void) memcpy((void *)&M1OFL_Control_Y, buffer, sizeof(ExtY_M1OFL_Control_T));
+  buffer += sizeof(ExtY_M1OFL_Control_T);
+  (void) memcpy((void *)M1OFL_Control_M, buffer, sizeof(RT_MODEL_M1OFL_Control_T));
+}
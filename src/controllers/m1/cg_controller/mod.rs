@@ -22,21 +22,16 @@ impl<'a> IOTags for Controller<'a> {
 }
 impl<'a> DOS for Controller<'a> {
     fn inputs(&mut self, data: Vec<IO<Vec<f64>>>) -> Result<&mut Self, DOSIOSError> {
-        if data.into_iter().fold(1, |mut a, io| {
+        if data.into_iter().try_fold(1, |mut a, io| {
             match io {
                 IO::M1HPLC { data: Some(values) } => {
-                    for (k, v) in values.into_iter().enumerate() {
-                        self.m1_hp_lc[k] = v;
-                    }
+                    self.m1_hp_lc.assign(values)?;
                     a -= 1;
                 }
                 _ => (),
             }
-            if a == 0 {
-                return a;
-            }
-            a
-        }) == 0
+            Ok::<_, DOSIOSError>(a)
+        })? == 0
         {
             Ok(self)
         } else {
@@ -51,3 +46,42 @@ impl<'a> DOS for Controller<'a> {
         }])
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controllers::SimulinkState;
+
+    fn outputs(m1_cg: &mut Controller) -> Vec<f64> {
+        m1_cg
+            .in_step_out(vec![jar::M1HPLC::with(vec![1f64; 42])])
+            .unwrap()
+            .and_then(|y| Option::<Vec<f64>>::from(&y[0]))
+            .unwrap()
+    }
+
+    #[test]
+    fn m1_cg_snapshot_reset() {
+        let mut m1_cg = Controller::new();
+        let y0 = outputs(&mut m1_cg);
+        let state = m1_cg.snapshot();
+        let y1 = outputs(&mut m1_cg);
+        assert_ne!(y0, y1);
+        m1_cg.restore(&state).unwrap();
+        assert_eq!(y1, outputs(&mut m1_cg));
+        m1_cg.reset();
+        assert_eq!(y0, outputs(&mut m1_cg));
+        assert!(m1_cg
+            .restore(&SimulinkState {
+                model: "MountDrives".to_string(),
+                data: state.data.clone(),
+            })
+            .is_err());
+        assert!(m1_cg
+            .restore(&SimulinkState {
+                model: state.model,
+                data: vec![0u8; 1],
+            })
+            .is_err());
+    }
+}
@@ -0,0 +1,40 @@
+/*
+ * File: M1HPloadcells_state.c
+ *
+ * Snapshot and restore of the 'M1HPloadcells' model states.
+ *
+ * The block signals, block states, external inputs and outputs and the
+ * real-time model object are copied to and from a contiguous byte buffer
+ * of size M1HPloadcells_state_size().
+ */
+
+#include <string.h>
+#include "M1HPloadcells.h"
+
+/* Size in bytes of the model state buffer */
+size_t M1HPloadcells_state_size(void)
+{
+  return sizeof(ExtU_M1HPloadcells_T)
+         + sizeof(ExtY_M1HPloadcells_T)
+         + sizeof(RT_MODEL_M1HPloadcells_T);
+}
+
+/* Copies the model states into buffer */
+void M1HPloadcells_state_get(unsigned char *buffer)
+{
+  (void) memcpy(buffer, (void *)&M1HPloadcells_U, sizeof(ExtU_M1HPloadcells_T));
+  buffer += sizeof(ExtU_M1HPloadcells_T);
+  (void) memcpy(buffer, (void *)&M1HPloadcells_Y, sizeof(ExtY_M1HPloadcells_T));
+  buffer += sizeof(ExtY_M1HPloadcells_T);
+  (void) memcpy(buffer, (void *)M1HPloadcells_M, sizeof(RT_MODEL_M1HPloadcells_T));
+}
+
+/* Copies buffer into the model states */
+void M1HPloadcells_state_set(const unsigned char *buffer)
+{
+  (void) memcpy((void *)&M1HPloadcells_U, buffer, sizeof(ExtU_M1HPloadcells_T));
+  buffer += sizeof(ExtU_M1HPloadcells_T);
+  (void) memcpy((void *)&M1HPloadcells_Y, buffer, sizeof(ExtY_M1HPloadcells_T));
+  buffer += sizeof(ExtY_M1HPloadcells_T);
+  (void) memcpy((void *)M1HPloadcells_M, buffer, sizeof(RT_MODEL_M1HPloadcells_T));
+}
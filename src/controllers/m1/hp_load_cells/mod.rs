@@ -23,27 +23,20 @@ impl<'a> IOTags for Controller<'a> {
 }
 impl<'a> DOS for Controller<'a> {
     fn inputs(&mut self, data: Vec<IO<Vec<f64>>>) -> Result<&mut Self, DOSIOSError> {
-        if data.into_iter().fold(2, |mut a, io| {
+        if data.into_iter().try_fold(2, |mut a, io| {
             match io {
                 IO::OSSHardpointD { data: Some(values) } => {
-                    for (k, v) in values.into_iter().enumerate() {
-                        self.m1_hp_d[k] = v;
-                    }
+                    self.m1_hp_d.assign(values)?;
                     a -= 1;
                 }
                 IO::M1HPCmd { data: Some(values) } => {
-                    for (k, v) in values.into_iter().enumerate() {
-                        self.m1_hp_cmd[k] = v;
-                    }
+                    self.m1_hp_cmd.assign(values)?;
                     a -= 1;
                 }
                 _ => (),
             }
-            if a == 0 {
-                return a;
-            }
-            a
-        }) == 0
+            Ok::<_, DOSIOSError>(a)
+        })? == 0
         {
             Ok(self)
         } else {
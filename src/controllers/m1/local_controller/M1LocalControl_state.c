@@ -0,0 +1,50 @@
+/*
+ * File: M1LocalControl_state.c
+ *
+ * Snapshot and restore of the 'M1LocalControl' model states.
+ *
+ * The block signals, block states, external inputs and outputs and the
+ * real-time model object are copied to and from a contiguous byte buffer
+ * of size M1LocalControl_state_size().
+ */
+
+#include <string.h>
+#include "M1LocalControl.h"
+
+/* Size in bytes of the model state buffer */
+size_t M1LocalControl_state_size(void)
+{
+  return sizeof(B_M1LocalControl_T)
+         + sizeof(DW_M1LocalControl_T)
+         + sizeof(ExtU_M1LocalControl_T)
+         + sizeof(ExtY_M1LocalControl_T)
+         + sizeof(RT_MODEL_M1LocalControl_T);
+}
+
+/* Copies the model states into buffer */
+void M1LocalControl_state_get(unsigned char *buffer)
+{
+  (void) memcpy(buffer, (void *)&M1LocalControl_B, sizeof(B_M1LocalControl_T));
+  buffer += sizeof(B_M1LocalControl_T);
+  (void) memcpy(buffer, (void *)&M1LocalControl_DW, sizeof(DW_M1LocalControl_T));
+  buffer += sizeof(DW_M1LocalControl_T);
+  (void) memcpy(buffer, (void *)&M1LocalControl_U, sizeof(ExtU_M1LocalControl_T));
+  buffer += sizeof(ExtU_M1LocalControl_T);
+  (void) memcpy(buffer, (void *)&M1LocalControl_Y, sizeof(ExtY_M1LocalControl_T));
+  buffer += sizeof(ExtY_M1LocalControl_T);
+  (void) memcpy(buffer, (void *)M1LocalControl_M, sizeof(RT_MODEL_M1LocalControl_T));
+}
+
+/* Copies buffer into the model states */
+void M1LocalControl_state_set(const unsigned char *buffer)
+{
+  (void) memcpy((void *)&M1LocalControl_B, buffer, sizeof(B_M1LocalControl_T));
+  buffer += sizeof(B_M1LocalControl_T);
+  (void) memcpy((void *)&M1LocalControl_DW, buffer, sizeof(DW_M1LocalControl_T));
+  buffer += sizeof(DW_M1LocalControl_T);
+  (void) memcpy((void *)&M1LocalControl_U, buffer, sizeof(ExtU_M1LocalControl_T));
+  buffer += sizeof(ExtU_M1LocalControl_T);
+  (void) memcpy((void *)&M1LocalControl_Y, buffer, sizeof(ExtY_M1LocalControl_T));
+  buffer += sizeof(ExtY_M1LocalControl_T);
+  (void) memcpy((void *)M1LocalControl_M, buffer, sizeof(RT_MODEL_M1LocalControl_T));
+}
@@ -1,5 +1,9 @@
+pub mod golden;
+pub mod linear;
 pub mod m1;
 pub mod mount;
 pub mod simulink;
-pub use simulink::Simulink;
+pub use simulink::{Simulink, SimulinkError, SimulinkState};
 pub mod state_space;
+pub mod summing_junction;
+pub use summing_junction::SummingJunction;
@@ -0,0 +1,50 @@
+/*
+ * File: MountControl0_state.c
+ *
+ * Snapshot and restore of the 'MountControl0' model states.
+ *
+ * The block signals, block states, external inputs and outputs and the
+ * real-time model object are copied to and from a contiguous byte buffer
+ * of size MountControl0_state_size().
+ */
+
+#include <string.h>
+#include "MountControl0.h"
+
+/* Size in bytes of the model state buffer */
+size_t MountControl0_state_size(void)
+{
+  return sizeof(B_MountControl0_T)
+         + sizeof(DW_MountControl0_T)
+         + sizeof(ExtU_MountControl0_T)
+         + sizeof(ExtY_MountControl0_T)
+         + sizeof(RT_MODEL_MountControl0_T);
+}
+
+/* Copies the model states into buffer */
+void MountControl0_state_get(unsigned char *buffer)
+{
+  (void) memcpy(buffer, (void *)&MountControl0_B, sizeof(B_MountControl0_T));
+  buffer += sizeof(B_MountControl0_T);
+  (void) memcpy(buffer, (void *)&MountControl0_DW, sizeof(DW_MountControl0_T));
+  buffer += sizeof(DW_MountControl0_T);
+  (void) memcpy(buffer, (void *)&MountControl0_U, sizeof(ExtU_MountControl0_T));
+  buffer += sizeof(ExtU_MountControl0_T);
+  (void) memcpy(buffer, (void *)&MountControl0_Y, sizeof(ExtY_MountControl0_T));
+  buffer += sizeof(ExtY_MountControl0_T);
+  (void) memcpy(buffer, (void *)MountControl0_M, sizeof(RT_MODEL_MountControl0_T));
+}
+
+/* Copies buffer into the model states */
+void MountControl0_state_set(const unsigned char *buffer)
+{
+  (void) memcpy((void *)&MountControl0_B, buffer, sizeof(B_MountControl0_T));
+  buffer += sizeof(B_MountControl0_T);
+  (void) memcpy((void *)&MountControl0_DW, buffer, sizeof(DW_MountControl0_T));
+  buffer += sizeof(DW_MountControl0_T);
+  (void) memcpy((void *)&MountControl0_U, buffer, sizeof(ExtU_MountControl0_T));
+  buffer += sizeof(ExtU_MountControl0_T);
+  (void) memcpy((void *)&MountControl0_Y, buffer, sizeof(ExtY_MountControl0_T));
+  buffer += sizeof(ExtY_MountControl0_T);
+  (void) memcpy((void *)MountControl0_M, buffer, sizeof(RT_MODEL_MountControl0_T));
+}
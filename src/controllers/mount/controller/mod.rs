@@ -1,5 +1,6 @@
 use crate::{
     build_controller, build_inputs, build_outputs, import_simulink,
+    controllers::mount::setpoint::WithSetPoint,
     io::{jar, Tags},
     IOTags, DOS, IO, DOSIOSError
 };
@@ -8,29 +9,41 @@ import_simulink!(MountControl0, U : (Mount_SP,3,Mount_FB,20), Y : (Mount_cmd,3))
 build_inputs!(
     SP,
     3,
+    3,
     0,
     OssAzDrive,
     20,
+    8,
     0,
     OssElDrive,
     20,
     8,
+    8,
     OssGirDrive,
     20,
+    4,
     16
 );
 build_outputs!(CMD, 3);
 build_controller!(MountControl0,
-                  U : (Mount_FB -> (OssAzDrive,oss_az_drive),
+                  U : (Mount_SP -> (SP,sp),
+                       Mount_FB -> (OssAzDrive,oss_az_drive),
                        Mount_FB -> (OssElDrive,oss_el_drive),
                        Mount_FB -> (OssGirDrive,oss_gir_drive)),
                   Y : (Mount_cmd -> (CMD,cmd))
 );
 
+impl<'a> Controller<'a> {
+    /// Creates a new controller with the [`MountSP`](jar::MountSP) setpoint declared as an input
+    pub fn with_setpoint() -> WithSetPoint<Self> {
+        WithSetPoint(Self::new())
+    }
+}
 impl<'a> IOTags for Controller<'a> {
     fn outputs_tags(&self) -> Vec<Tags> {
         vec![jar::MountCmd::new()]
     }
+    // MountSP is an optional input that is left out of the tags, see `Controller::with_setpoint`
     fn inputs_tags(&self) -> Vec<Tags> {
         vec![
             jar::OSSAzDriveD::new(),
@@ -41,33 +54,27 @@ impl<'a> IOTags for Controller<'a> {
 }
 impl<'a> DOS for Controller<'a> {
     fn inputs(&mut self, data: Vec<IO<Vec<f64>>>) -> Result<&mut Self, DOSIOSError> {
-        if data.into_iter().fold(3, |mut a, io| {
+        if data.into_iter().try_fold(3, |mut a, io| {
             match io {
+                IO::MountSP { data: Some(values) } => {
+                    self.sp.assign(values)?;
+                }
                 IO::OSSAzDriveD { data: Some(values) } => {
-                    for (k, v) in values.into_iter().enumerate() {
-                        self.oss_az_drive[k] = v;
-                    }
+                    self.oss_az_drive.assign(values)?;
                     a -= 1;
                 }
                 IO::OSSElDriveD { data: Some(values) } => {
-                    for (k, v) in values.into_iter().enumerate() {
-                        self.oss_el_drive[k] = v;
-                    }
+                    self.oss_el_drive.assign(values)?;
                     a -= 1;
                 }
                 IO::OSSGIRDriveD { data: Some(values) } => {
-                    for (k, v) in values.into_iter().enumerate() {
-                        self.oss_gir_drive[k] = v;
-                    }
+                    self.oss_gir_drive.assign(values)?;
                     a -= 1;
                 }
                 _ => (),
             }
-            if a == 0 {
-                return a;
-            }
-            a
-        }) == 0
+            Ok::<_, DOSIOSError>(a)
+        })? == 0
         {
             Ok(self)
         } else {
@@ -0,0 +1,50 @@
+/*
+ * File: MountDrives_state.c
+ *
+ * Snapshot and restore of the 'MountDrives' model states.
+ *
+ * The block signals, block states, external inputs and outputs and the
+ * real-time model object are copied to and from a contiguous byte buffer
+ * of size MountDrives_state_size().
+ */
+
+#include <string.h>
+#include "MountDrives.h"
+
+/* Size in bytes of the model state buffer */
+size_t MountDrives_state_size(void)
+{
+  return sizeof(B_MountDrives_T)
+         + sizeof(DW_MountDrives_T)
+         + sizeof(ExtU_MountDrives_T)
+         + sizeof(ExtY_MountDrives_T)
+         + sizeof(RT_MODEL_MountDrives_T);
+}
+
+/* Copies the model states into buffer */
+void MountDrives_state_get(unsigned char *buffer)
+{
+  (void) memcpy(buffer, (void *)&MountDrives_B, sizeof(B_MountDrives_T));
+  buffer += sizeof(B_MountDrives_T);
+  (void) memcpy(buffer, (void *)&MountDrives_DW, sizeof(DW_MountDrives_T));
+  buffer += sizeof(DW_MountDrives_T);
+  (void) memcpy(buffer, (void *)&MountDrives_U, sizeof(ExtU_MountDrives_T));
+  buffer += sizeof(ExtU_MountDrives_T);
+  (void) memcpy(buffer, (void *)&MountDrives_Y, sizeof(ExtY_MountDrives_T));
+  buffer += sizeof(ExtY_MountDrives_T);
+  (void) memcpy(buffer, (void *)MountDrives_M, sizeof(RT_MODEL_MountDrives_T));
+}
+
+/* Copies buffer into the model states */
+void MountDrives_state_set(const unsigned char *buffer)
+{
+  (void) memcpy((void *)&MountDrives_B, buffer, sizeof(B_MountDrives_T));
+  buffer += sizeof(B_MountDrives_T);
+  (void) memcpy((void *)&MountDrives_DW, buffer, sizeof(DW_MountDrives_T));
+  buffer += sizeof(DW_MountDrives_T);
+  (void) memcpy((void *)&MountDrives_U, buffer, sizeof(ExtU_MountDrives_T));
+  buffer += sizeof(ExtU_MountDrives_T);
+  (void) memcpy((void *)&MountDrives_Y, buffer, sizeof(ExtY_MountDrives_T));
+  buffer += sizeof(ExtY_MountDrives_T);
+  (void) memcpy((void *)MountDrives_M, buffer, sizeof(RT_MODEL_MountDrives_T));
+}
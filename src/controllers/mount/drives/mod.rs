@@ -8,15 +8,19 @@ import_simulink!(MountDrives, U : (Mount_cmd,3,Mount_pos,20), Y : (Mount_F,20));
 build_inputs!(
     CMD,
     3,
+    3,
     0,
     OssAzDrive,
     20,
+    8,
     0,
     OssElDrive,
     20,
     8,
+    8,
     OssGirDrive,
     20,
+    4,
     16
 );
 build_outputs!(
@@ -63,39 +67,28 @@ impl<'a> IOTags for Controller<'a> {
 }
 impl<'a> DOS for Controller<'a> {
     fn inputs(&mut self, data: Vec<IO<Vec<f64>>>) -> Result<&mut Self, DOSIOSError> {
-        if data.into_iter().fold(4, |mut a, io| {
+        if data.into_iter().try_fold(4, |mut a, io| {
             match io {
                 IO::MountCmd { data: Some(values) } => {
-                    for (k, v) in values.into_iter().enumerate() {
-                        self.cmd[k] = v;
-                    }
+                    self.cmd.assign(values)?;
                     a -= 1;
                 }
                 IO::OSSAzDriveD { data: Some(values) } => {
-                    for (k, v) in values.into_iter().enumerate() {
-                        self.oss_az_drive_d[k] = v;
-                    }
+                    self.oss_az_drive_d.assign(values)?;
                     a -= 1;
                 }
                 IO::OSSElDriveD { data: Some(values) } => {
-                    for (k, v) in values.into_iter().enumerate() {
-                        self.oss_el_drive_d[k] = v;
-                    }
+                    self.oss_el_drive_d.assign(values)?;
                     a -= 1;
                 }
                 IO::OSSGIRDriveD { data: Some(values) } => {
-                    for (k, v) in values.into_iter().enumerate() {
-                        self.oss_gir_drive_d[k] = v;
-                    }
+                    self.oss_gir_drive_d.assign(values)?;
                     a -= 1;
                 }
                 _ => (),
             }
-            if a == 0 {
-                return a;
-            }
-            a
-        }) == 0
+            Ok::<_, DOSIOSError>(a)
+        })? == 0
         {
             Ok(self)
         } else {
@@ -116,3 +109,36 @@ impl<'a> DOS for Controller<'a> {
         ])
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ones() -> Vec<IO<Vec<f64>>> {
+        vec![
+            jar::MountCmd::with(vec![1f64; 3]),
+            jar::OSSAzDriveD::with(vec![1f64; 8]),
+            jar::OSSElDriveD::with(vec![1f64; 8]),
+            jar::OSSGIRDriveD::with(vec![1f64; 4]),
+        ]
+    }
+    fn outputs(y: Option<Vec<IO<Vec<f64>>>>) -> Vec<f64> {
+        y.unwrap()
+            .iter()
+            .flat_map(|x| Option::<Vec<f64>>::from(x).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn mount_drives_snapshot_reset() {
+        let mut mnt_drives = Controller::new();
+        let y0 = outputs(mnt_drives.in_step_out(ones()).unwrap());
+        let state = mnt_drives.snapshot();
+        let y1 = outputs(mnt_drives.in_step_out(ones()).unwrap());
+        assert_ne!(y0, y1);
+        mnt_drives.restore(&state).unwrap();
+        assert_eq!(y1, outputs(mnt_drives.in_step_out(ones()).unwrap()));
+        mnt_drives.reset();
+        assert_eq!(y0, outputs(mnt_drives.in_step_out(ones()).unwrap()));
+    }
+}
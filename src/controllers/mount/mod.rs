@@ -1,3 +1,4 @@
 pub mod controller;
 pub mod drives;
 pub mod pdr;
+pub mod setpoint;
@@ -0,0 +1,50 @@
+/*
+ * File: Mount_Control_state.c
+ *
+ * Snapshot and restore of the 'Mount_Control' model states.
+ *
+ * The block signals, block states, external inputs and outputs and the
+ * real-time model object are copied to and from a contiguous byte buffer
+ * of size Mount_Control_state_size().
+ */
+
+#include <string.h>
+#include "Mount_Control.h"
+
+/* Size in bytes of the model state buffer */
+size_t Mount_Control_state_size(void)
+{
+  return sizeof(B_Mount_Control_T)
+         + sizeof(DW_Mount_Control_T)
+         + sizeof(ExtU_Mount_Control_T)
+         + sizeof(ExtY_Mount_Control_T)
+         + sizeof(RT_MODEL_Mount_Control_T);
+}
+
+/* Copies the model states into buffer */
+void Mount_Control_state_get(unsigned char *buffer)
+{
+  (void) memcpy(buffer, (void *)&Mount_Control_B, sizeof(B_Mount_Control_T));
+  buffer += sizeof(B_Mount_Control_T);
+  (void) memcpy(buffer, (void *)&Mount_Control_DW, sizeof(DW_Mount_Control_T));
+  buffer += sizeof(DW_Mount_Control_T);
+  (void) memcpy(buffer, (void *)&Mount_Control_U, sizeof(ExtU_Mount_Control_T));
+  buffer += sizeof(ExtU_Mount_Control_T);
+  (void) memcpy(buffer, (void *)&Mount_Control_Y, sizeof(ExtY_Mount_Control_T));
+  buffer += sizeof(ExtY_Mount_Control_T);
+  (void) memcpy(buffer, (void *)Mount_Control_M, sizeof(RT_MODEL_Mount_Control_T));
+}
+
+/* Copies buffer into the model states */
+void Mount_Control_state_set(const unsigned char *buffer)
+{
+  (void) memcpy((void *)&Mount_Control_B, buffer, sizeof(B_Mount_Control_T));
+  buffer += sizeof(B_Mount_Control_T);
+  (void) memcpy((void *)&Mount_Control_DW, buffer, sizeof(DW_Mount_Control_T));
+  buffer += sizeof(DW_Mount_Control_T);
+  (void) memcpy((void *)&Mount_Control_U, buffer, sizeof(ExtU_Mount_Control_T));
+  buffer += sizeof(ExtU_Mount_Control_T);
+  (void) memcpy((void *)&Mount_Control_Y, buffer, sizeof(ExtY_Mount_Control_T));
+  buffer += sizeof(ExtY_Mount_Control_T);
+  (void) memcpy((void *)Mount_Control_M, buffer, sizeof(RT_MODEL_Mount_Control_T));
+}
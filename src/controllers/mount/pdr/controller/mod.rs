@@ -1,5 +1,6 @@
 use crate::{
     build_controller, build_inputs, build_outputs, import_simulink,
+    controllers::mount::setpoint::WithSetPoint,
     io::{jar, Tags},
     DOSIOSError, IOTags, DOS, IO,
 };
@@ -8,29 +9,41 @@ import_simulink!(Mount_Control, U : (Mount_SP,3,Mount_FB,14), Y : (Mount_cmd,3))
 build_inputs!(
     SP,
     3,
+    3,
     0,
     OssAzDrive,
     14,
+    4,
     0,
     OssElDrive,
     14,
+    6,
     4,
     OssGirDrive,
     14,
+    4,
     10
 );
 build_outputs!(MountCmd, 3);
 build_controller!(Mount_Control,
-                  U : (Mount_FB -> (OssAzDrive,oss_az_drive),
+                  U : (Mount_SP -> (SP,sp),
+                       Mount_FB -> (OssAzDrive,oss_az_drive),
                        Mount_FB -> (OssElDrive,oss_el_drive),
                        Mount_FB -> (OssGirDrive,oss_gir_drive)),
                   Y : (Mount_cmd -> (MountCmd,cmd))
 );
 
+impl<'a> Controller<'a> {
+    /// Creates a new controller with the [`MountSP`](jar::MountSP) setpoint declared as an input
+    pub fn with_setpoint() -> WithSetPoint<Self> {
+        WithSetPoint(Self::new())
+    }
+}
 impl<'a> IOTags for Controller<'a> {
     fn outputs_tags(&self) -> Vec<Tags> {
         vec![jar::MountCmd::new()]
     }
+    // MountSP is an optional input that is left out of the tags, see `Controller::with_setpoint`
     fn inputs_tags(&self) -> Vec<Tags> {
         vec![
             jar::OSSAzEncoderAngle::new(),
@@ -41,33 +54,27 @@ impl<'a> IOTags for Controller<'a> {
 }
 impl<'a> DOS for Controller<'a> {
     fn inputs(&mut self, data: Vec<IO<Vec<f64>>>) -> Result<&mut Self, DOSIOSError> {
-        if data.into_iter().fold(3, |mut a, io| {
+        if data.into_iter().try_fold(3, |mut a, io| {
             match io {
+                IO::MountSP { data: Some(values) } => {
+                    self.sp.assign(values)?;
+                }
                 IO::OSSAzEncoderAngle { data: Some(values) } => {
-                    for (k, v) in values.into_iter().enumerate() {
-                        self.oss_az_drive[k] = v;
-                    }
+                    self.oss_az_drive.assign(values)?;
                     a -= 1;
                 }
                 IO::OSSElEncoderAngle { data: Some(values) } => {
-                    for (k, v) in values.into_iter().enumerate() {
-                        self.oss_el_drive[k] = v;
-                    }
+                    self.oss_el_drive.assign(values)?;
                     a -= 1;
                 }
                 IO::OSSRotEncoderAngle { data: Some(values) } => {
-                    for (k, v) in values.into_iter().enumerate() {
-                        self.oss_gir_drive[k] = v;
-                    }
+                    self.oss_gir_drive.assign(values)?;
                     a -= 1;
                 }
                 _ => (),
             }
-            if a == 0 {
-                return a;
-            }
-            a
-        }) == 0
+            Ok::<_, DOSIOSError>(a)
+        })? == 0
         {
             Ok(self)
         } else {
@@ -89,8 +96,8 @@ mod tests {
     fn pdr_mount_control_zeros_test() {
         let mut mnt_ctrl = Controller::new();
         let u = vec![
-            jar::OSSAzEncoderAngle::with(vec![0f64; 8]),
-            jar::OSSElEncoderAngle::with(vec![0f64; 8]),
+            jar::OSSAzEncoderAngle::with(vec![0f64; 4]),
+            jar::OSSElEncoderAngle::with(vec![0f64; 6]),
             jar::OSSRotEncoderAngle::with(vec![0f64; 4]),
         ];
         let y = mnt_ctrl.in_step_out(u).unwrap();
@@ -102,8 +109,8 @@ mod tests {
         let mut mnt_ctrl = Controller::new();
         for k in 0..5 {
             let u = vec![
-                jar::OSSAzEncoderAngle::with(vec![1f64; 8]),
-                jar::OSSElEncoderAngle::with(vec![1f64; 8]),
+                jar::OSSAzEncoderAngle::with(vec![1f64; 4]),
+                jar::OSSElEncoderAngle::with(vec![1f64; 6]),
                 jar::OSSRotEncoderAngle::with(vec![1f64; 4]),
             ];
             let y = mnt_ctrl.in_step_out(u).unwrap();
@@ -0,0 +1,50 @@
+/*
+ * File: Mount_Drv_PDR2021_state.c
+ *
+ * Snapshot and restore of the 'Mount_Drv_PDR2021' model states.
+ *
+ * The block signals, block states, external inputs and outputs and the
+ * real-time model object are copied to and from a contiguous byte buffer
+ * of size Mount_Drv_PDR2021_state_size().
+ */
+
+#include <string.h>
+#include "Mount_Drv_PDR2021.h"
+
+/* Size in bytes of the model state buffer */
+size_t Mount_Drv_PDR2021_state_size(void)
+{
+  return sizeof(B_Mount_Drv_PDR2021_T)
+         + sizeof(DW_Mount_Drv_PDR2021_T)
+         + sizeof(ExtU_Mount_Drv_PDR2021_T)
+         + sizeof(ExtY_Mount_Drv_PDR2021_T)
+         + sizeof(RT_MODEL_Mount_Drv_PDR2021_T);
+}
+
+/* Copies the model states into buffer */
+void Mount_Drv_PDR2021_state_get(unsigned char *buffer)
+{
+  (void) memcpy(buffer, (void *)&Mount_Drv_PDR2021_B, sizeof(B_Mount_Drv_PDR2021_T));
+  buffer += sizeof(B_Mount_Drv_PDR2021_T);
+  (void) memcpy(buffer, (void *)&Mount_Drv_PDR2021_DW, sizeof(DW_Mount_Drv_PDR2021_T));
+  buffer += sizeof(DW_Mount_Drv_PDR2021_T);
+  (void) memcpy(buffer, (void *)&Mount_Drv_PDR2021_U, sizeof(ExtU_Mount_Drv_PDR2021_T));
+  buffer += sizeof(ExtU_Mount_Drv_PDR2021_T);
+  (void) memcpy(buffer, (void *)&Mount_Drv_PDR2021_Y, sizeof(ExtY_Mount_Drv_PDR2021_T));
+  buffer += sizeof(ExtY_Mount_Drv_PDR2021_T);
+  (void) memcpy(buffer, (void *)Mount_Drv_PDR2021_M, sizeof(RT_MODEL_Mount_Drv_PDR2021_T));
+}
+
+/* Copies buffer into the model states */
+void Mount_Drv_PDR2021_state_set(const unsigned char *buffer)
+{
+  (void) memcpy((void *)&Mount_Drv_PDR2021_B, buffer, sizeof(B_Mount_Drv_PDR2021_T));
+  buffer += sizeof(B_Mount_Drv_PDR2021_T);
+  (void) memcpy((void *)&Mount_Drv_PDR2021_DW, buffer, sizeof(DW_Mount_Drv_PDR2021_T));
+  buffer += sizeof(DW_Mount_Drv_PDR2021_T);
+  (void) memcpy((void *)&Mount_Drv_PDR2021_U, buffer, sizeof(ExtU_Mount_Drv_PDR2021_T));
+  buffer += sizeof(ExtU_Mount_Drv_PDR2021_T);
+  (void) memcpy((void *)&Mount_Drv_PDR2021_Y, buffer, sizeof(ExtY_Mount_Drv_PDR2021_T));
+  buffer += sizeof(ExtY_Mount_Drv_PDR2021_T);
+  (void) memcpy((void *)Mount_Drv_PDR2021_M, buffer, sizeof(RT_MODEL_Mount_Drv_PDR2021_T));
+}
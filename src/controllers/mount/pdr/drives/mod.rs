@@ -8,15 +8,19 @@ import_simulink!(Mount_Drv_PDR2021, U : (Mount_cmd,3,Mount_pos,14), Y : (Mount_F
 build_inputs!(
     MountCmd,
     3,
+    3,
     0,
     OssAzDrive,
     14,
+    4,
     0,
     OssElDrive,
     14,
     6,
+    4,
     OssGirDrive,
     14,
+    4,
     10
 );
 build_outputs!(
@@ -63,39 +67,28 @@ impl<'a> IOTags for Controller<'a> {
 }
 impl<'a> DOS for Controller<'a> {
     fn inputs(&mut self, data: Vec<IO<Vec<f64>>>) -> Result<&mut Self, DOSIOSError> {
-        if data.into_iter().fold(4, |mut a, io| {
+        if data.into_iter().try_fold(4, |mut a, io| {
             match io {
                 IO::MountCmd { data: Some(values) } => {
-                    for (k, v) in values.into_iter().enumerate() {
-                        self.cmd[k] = v;
-                    }
+                    self.cmd.assign(values)?;
                     a -= 1;
                 }
                 IO::OSSAzEncoderAngle { data: Some(values) } => {
-                    for (k, v) in values.into_iter().enumerate() {
-                        self.oss_az_drive_d[k] = v;
-                    }
+                    self.oss_az_drive_d.assign(values)?;
                     a -= 1;
                 }
                 IO::OSSElEncoderAngle { data: Some(values) } => {
-                    for (k, v) in values.into_iter().enumerate() {
-                        self.oss_el_drive_d[k] = v;
-                    }
+                    self.oss_el_drive_d.assign(values)?;
                     a -= 1;
                 }
                 IO::OSSRotEncoderAngle { data: Some(values) } => {
-                    for (k, v) in values.into_iter().enumerate() {
-                        self.oss_gir_drive_d[k] = v;
-                    }
+                    self.oss_gir_drive_d.assign(values)?;
                     a -= 1;
                 }
                 _ => (),
             }
-            if a == 0 {
-                return a;
-            }
-            a
-        }) == 0
+            Ok::<_, DOSIOSError>(a)
+        })? == 0
         {
             Ok(self)
         } else {
@@ -127,8 +120,8 @@ mod tests {
         for _ in 0..5 {
             let u = vec![
                 jar::MountCmd::with(vec![0f64; 3]),
-                jar::OSSAzEncoderAngle::with(vec![0f64; 6]),
-                jar::OSSElEncoderAngle::with(vec![0f64; 4]),
+                jar::OSSAzEncoderAngle::with(vec![0f64; 4]),
+                jar::OSSElEncoderAngle::with(vec![0f64; 6]),
                 jar::OSSRotEncoderAngle::with(vec![0f64; 4]),
             ];
             let y = mnt_drives.in_step_out(u).unwrap();
@@ -142,12 +135,24 @@ mod tests {
         for _ in 0..5 {
             let u = vec![
                 jar::MountCmd::with(vec![1f64; 3]),
-                jar::OSSAzEncoderAngle::with(vec![1f64; 6]),
-                jar::OSSElEncoderAngle::with(vec![1f64; 4]),
+                jar::OSSAzEncoderAngle::with(vec![1f64; 4]),
+                jar::OSSElEncoderAngle::with(vec![1f64; 6]),
                 jar::OSSRotEncoderAngle::with(vec![1f64; 4]),
             ];
             let y = mnt_drives.in_step_out(u).unwrap();
             println!("PDR MOUNT DRIVE ONES TEST: {:#?}", y);
         }
     }
+
+    #[test]
+    fn pdr_mount_drive_inputs_size() {
+        let mut mnt_drives = Controller::new();
+        let u = vec![
+            jar::MountCmd::with(vec![0f64; 3]),
+            jar::OSSAzEncoderAngle::with(vec![0f64; 6]),
+            jar::OSSElEncoderAngle::with(vec![0f64; 6]),
+            jar::OSSRotEncoderAngle::with(vec![0f64; 4]),
+        ];
+        assert!(mnt_drives.inputs(u).is_err());
+    }
 }
@@ -0,0 +1,289 @@
+//! Mount setpoint generator
+//!
+//! The setpoint generator outputs the azimuth, elevation and GIR setpoints, in radians, of the mount controllers ([`mount::controller`](crate::controllers::mount::controller) and [`mount::pdr::controller`](crate::controllers::mount::pdr::controller)).
+//! The setpoints are the sum of
+//!  - constant offsets,
+//!  - steps,
+//!  - ramps,
+//!  - and the sidereal tracking of a target.
+//!
+//! The sidereal tracking setpoints are given relative to the target position at the start of the simulation as the telescope FEM is a linear model around the initial pointing.
+//! The azimuth is counted from the North towards the East and the GIR follows the parallactic angle to compensate the field rotation.
+//!
+//! The setpoint is an optional input of the mount controllers that is left out of their [`inputs_tags`](IOTags::inputs_tags),
+//! a controller created with `Controller::with_setpoint` declares it and receives the setpoint in a [`Model`](crate::model::Model).
+//!
+//! # Example
+//! ```no_run
+//! use dos::{controllers::mount::setpoint::{MountSetPoint, SiderealTracking}, DOS};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut mount_sp = MountSetPoint::new(1e3)
+//!     .offset([0., 1e-6, 0.])
+//!     .step_at(1., [1e-6, 0., 0.])
+//!     .ramp(2., 1., [0., 0., 1e-6])
+//!     .tracking(SiderealTracking::new(
+//!         30f64.to_radians(),
+//!         -20f64.to_radians(),
+//!         -29.0146f64.to_radians(),
+//!         0.,
+//!     ));
+//! let sp = mount_sp.step()?.outputs();
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{
+    io::{jar, Tags},
+    DOSIOSError, IOTags, DOS, IO,
+};
+use std::f64::consts::PI;
+
+/// Sidereal rate in radians per second
+pub const SIDEREAL_RATE: f64 = 2. * PI / 86164.0905;
+
+/// Sidereal tracking of a target
+#[derive(Debug, Clone)]
+pub struct SiderealTracking {
+    /// target right ascension in radians
+    pub right_ascension: f64,
+    /// target declination in radians
+    pub declination: f64,
+    /// site latitude in radians
+    pub latitude: f64,
+    /// local sidereal time at the start of the simulation in radians
+    pub local_sidereal_time: f64,
+}
+impl SiderealTracking {
+    /// Creates a new sidereal tracking from the target right ascension and declination, the site latitude and the local sidereal time at the start of the simulation, all in radians
+    pub fn new(
+        right_ascension: f64,
+        declination: f64,
+        latitude: f64,
+        local_sidereal_time: f64,
+    ) -> Self {
+        Self {
+            right_ascension,
+            declination,
+            latitude,
+            local_sidereal_time,
+        }
+    }
+    /// Returns the target azimuth, elevation and parallactic angle in radians at time `t` in seconds
+    pub fn az_el_pa(&self, t: f64) -> [f64; 3] {
+        let h = self.local_sidereal_time + SIDEREAL_RATE * t - self.right_ascension;
+        let (sin_h, cos_h) = h.sin_cos();
+        let (sin_d, cos_d) = self.declination.sin_cos();
+        let (sin_l, cos_l) = self.latitude.sin_cos();
+        let el = (sin_l * sin_d + cos_l * cos_d * cos_h).asin();
+        let az = (-cos_d * sin_h).atan2(sin_d * cos_l - cos_d * sin_l * cos_h);
+        let pa = sin_h.atan2(sin_l / cos_l * cos_d - sin_d * cos_h);
+        [az, el, pa]
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Profile {
+    Offset([f64; 3]),
+    Step {
+        time: f64,
+        value: [f64; 3],
+    },
+    Ramp {
+        time: f64,
+        duration: f64,
+        value: [f64; 3],
+    },
+}
+impl Profile {
+    fn at(&self, t: f64) -> [f64; 3] {
+        match self {
+            Profile::Offset(value) => *value,
+            Profile::Step { time, value } => {
+                if t >= *time {
+                    *value
+                } else {
+                    [0f64; 3]
+                }
+            }
+            Profile::Ramp {
+                time,
+                duration,
+                value,
+            } => {
+                let r = ((t - time) / duration).clamp(0f64, 1f64);
+                [r * value[0], r * value[1], r * value[2]]
+            }
+        }
+    }
+}
+
+/// Mount setpoint generator
+#[derive(Debug, Clone)]
+pub struct MountSetPoint {
+    sampling_rate: f64,
+    profiles: Vec<Profile>,
+    tracking: Option<(SiderealTracking, [f64; 3])>,
+    index: Option<usize>,
+}
+impl MountSetPoint {
+    /// Creates a new setpoint generator with the sampling rate in Hz
+    pub fn new(sampling_rate: f64) -> Self {
+        Self {
+            sampling_rate,
+            profiles: vec![],
+            tracking: None,
+            index: None,
+        }
+    }
+    /// Adds constant azimuth, elevation and GIR offsets
+    pub fn offset(mut self, value: [f64; 3]) -> Self {
+        self.profiles.push(Profile::Offset(value));
+        self
+    }
+    /// Adds azimuth, elevation and GIR steps at time `time` in seconds
+    pub fn step_at(mut self, time: f64, value: [f64; 3]) -> Self {
+        self.profiles.push(Profile::Step { time, value });
+        self
+    }
+    /// Adds azimuth, elevation and GIR ramps starting at time `time` and reaching `value` after `duration`, in seconds
+    pub fn ramp(mut self, time: f64, duration: f64, value: [f64; 3]) -> Self {
+        assert!(duration > 0f64, "the ramp duration must be greater than 0");
+        self.profiles.push(Profile::Ramp {
+            time,
+            duration,
+            value,
+        });
+        self
+    }
+    /// Adds the sidereal tracking of a target
+    pub fn tracking(self, tracking: SiderealTracking) -> Self {
+        let origin = tracking.az_el_pa(0f64);
+        Self {
+            tracking: Some((tracking, origin)),
+            ..self
+        }
+    }
+    /// Returns the current simulation time in seconds
+    pub fn time(&self) -> f64 {
+        self.index.unwrap_or(0) as f64 / self.sampling_rate
+    }
+    /// Returns the azimuth, elevation and GIR setpoints at time `t` in seconds
+    pub fn setpoint(&self, t: f64) -> [f64; 3] {
+        let mut sp = self
+            .tracking
+            .as_ref()
+            .map_or([0f64; 3], |(tracking, origin)| {
+                let az_el_pa = tracking.az_el_pa(t);
+                let mut delta = [0f64; 3];
+                for i in 0..3 {
+                    // wrapping to [-pi,pi]
+                    delta[i] = (az_el_pa[i] - origin[i] + PI).rem_euclid(2. * PI) - PI;
+                }
+                delta
+            });
+        self.profiles.iter().for_each(|p| {
+            let v = p.at(t);
+            sp.iter_mut().zip(v.iter()).for_each(|(sp, v)| *sp += v);
+        });
+        sp
+    }
+}
+impl Iterator for MountSetPoint {
+    type Item = ();
+    fn next(&mut self) -> Option<Self::Item> {
+        self.index = self.index.map_or(Some(0), |x| Some(x + 1));
+        Some(())
+    }
+}
+impl IOTags for MountSetPoint {
+    fn outputs_tags(&self) -> Vec<Tags> {
+        vec![jar::MountSP::new()]
+    }
+    fn inputs_tags(&self) -> Vec<Tags> {
+        vec![]
+    }
+}
+impl DOS for MountSetPoint {
+    fn inputs(&mut self, _: Vec<IO<Vec<f64>>>) -> Result<&mut Self, DOSIOSError> {
+        Err(DOSIOSError::Inputs("MountSetPoint takes no inputs".into()))
+    }
+    fn outputs(&mut self) -> Option<Vec<IO<Vec<f64>>>> {
+        Some(vec![jar::MountSP::with(
+            self.setpoint(self.time()).to_vec(),
+        )])
+    }
+}
+
+/// Mount controller with the [`MountSP`](crate::io::jar::MountSP) setpoint declared as an input
+pub struct WithSetPoint<T>(pub T);
+impl<T: IOTags> IOTags for WithSetPoint<T> {
+    fn outputs_tags(&self) -> Vec<Tags> {
+        self.0.outputs_tags()
+    }
+    fn inputs_tags(&self) -> Vec<Tags> {
+        let mut tags = self.0.inputs_tags();
+        tags.push(jar::MountSP::new());
+        tags
+    }
+    fn outputs_rate(&self) -> Option<f64> {
+        self.0.outputs_rate()
+    }
+}
+impl<T: DOS> DOS for WithSetPoint<T> {
+    fn inputs(&mut self, data: Vec<IO<Vec<f64>>>) -> Result<&mut Self, DOSIOSError> {
+        self.0.inputs(data)?;
+        Ok(self)
+    }
+    fn outputs(&mut self) -> Option<Vec<IO<Vec<f64>>>> {
+        self.0.outputs()
+    }
+}
+impl<T: Iterator> Iterator for WithSetPoint<T> {
+    type Item = T::Item;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sidereal_tracking_meridian() {
+        // a target on the meridian culminates at 90-|latitude-declination| of elevation
+        let latitude = -29f64.to_radians();
+        let declination = -60f64.to_radians();
+        let tracking = SiderealTracking::new(1., declination, latitude, 1.);
+        let [az, el, pa] = tracking.az_el_pa(0.);
+        assert!((el - 59f64.to_radians()).abs() < 1e-12);
+        assert!((az.abs() - PI).abs() < 1e-12);
+        assert!(pa.abs() < 1e-12);
+    }
+
+    #[test]
+    fn setpoint_model() {
+        use crate::{controllers::mount::pdr, model::Model};
+        let mut mount_sp = MountSetPoint::new(1e3).step_at(0., [1e-6, 0., 0.]);
+        let mut mount_ctrl = pdr::controller::Controller::with_setpoint();
+        assert!(mount_ctrl.inputs_tags().contains(&jar::MountSP::new()));
+        let mut simulation = Model::new()
+            .component("mount controller", &mut mount_ctrl)
+            .component("mount setpoint", &mut mount_sp)
+            .initial(jar::OSSAzEncoderAngle::with(vec![0f64; 4]))
+            .initial(jar::OSSElEncoderAngle::with(vec![0f64; 6]))
+            .initial(jar::OSSRotEncoderAngle::with(vec![0f64; 4]))
+            .build()
+            .unwrap();
+        assert_eq!(
+            simulation.report().order,
+            vec!["mount setpoint", "mount controller"]
+        );
+        assert_eq!(
+            simulation.report().unused,
+            vec![("mount controller".to_string(), "MountCmd".to_string())]
+        );
+        assert_eq!(simulation.run(Some(3)).unwrap(), 3);
+    }
+}
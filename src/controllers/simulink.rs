@@ -10,11 +10,75 @@
 //! build_inputs!(Out1,3)
 //! build_controller!(SimControl, U: (SimIn1 -> (In1,in1)), Y: (SimOut1 -> (Out1,out1)))
 //! ```
+//!
+//! ## State management
+//! The controller states can be reset to their initial values with [`reset`](Simulink::reset),
+//! saved with [`snapshot`](Simulink::snapshot) and restored with [`restore`](Simulink::restore):
+//! ```ignore
+//! let mut ctrl = Controller::new();
+//! let checkpoint = ctrl.snapshot();
+//! // ... run the controller
+//! ctrl.restore(&checkpoint)?;
+//! // ... run the controller again from the checkpoint
+//! ctrl.reset();
+//! ```
+//! The Simulink generated code stores the model states in global variables,
+//! so all the instances of the same controller share the same states.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug)]
+pub enum SimulinkError {
+    Model { expected: String, found: String },
+    StateSize { model: String, expected: usize, found: usize },
+}
+impl fmt::Display for SimulinkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Model { expected, found } => {
+                write!(f, "cannot restore {} states into {}", found, expected)
+            }
+            Self::StateSize {
+                model,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{} states size mismatch: expected {} bytes, found {}",
+                model, expected, found
+            ),
+        }
+    }
+}
+impl std::error::Error for SimulinkError {}
+
+/// Simulink controller states
+///
+/// The states are the raw bytes of the Simulink block signals, block states (DWork), external inputs and outputs and real-time model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulinkState {
+    /// Simulink controller name
+    pub model: String,
+    /// Simulink controller states
+    pub data: Vec<u8>,
+}
 
 pub trait Simulink {
     fn initialize(&mut self);
     fn __step__(&self);
     fn terminate(&self);
+    /// Resets the controller states to their initial values
+    fn reset(&mut self) {
+        self.terminate();
+        self.initialize();
+    }
+    /// Returns a copy of the controller states
+    fn snapshot(&self) -> SimulinkState;
+    /// Restores the controller states from a previous snapshot
+    ///
+    /// Returns an error if the snapshot belongs to another controller or if its size does not match the controller states size
+    fn restore(&mut self, state: &SimulinkState) -> Result<(), SimulinkError>;
 }
 
 /// Import Simulink C definitions
@@ -45,6 +109,9 @@ macro_rules! import_simulink {
             fn [<$controller _initialize>]();
             fn [<$controller _step>]();
             fn [<$controller _terminate>]();
+            fn [<$controller _state_size>]() -> usize;
+            fn [<$controller _state_get>](buffer: *mut u8);
+            fn [<$controller _state_set>](buffer: *const u8);
             static mut [<$controller _U>]: [<ExtU_ $controller _T>];
             static mut [<$controller _Y>]: [<ExtY_ $controller _T>];
         }}
@@ -53,10 +120,13 @@ macro_rules! import_simulink {
 
 /// Build the controller inputs
 ///
-/// An input definition is: `(<enum name,size>,<...>,...)` or `(<enum name,size,offset>,<...>,...)` with
+/// An input definition is: `(<enum name,size>,<...>,...)` or `(<enum name,size,sub-size,offset>,<...>,...)` with
 ///  - `enum name`: the name of the input enum variant (U::name)
 ///  - `size`: the size of the corresponding Simulink input
+///  - `sub-size`: the size of the input within the corresponding Simulink input
 ///  - `offset`: the pointer offset in the corresponding Simulink input
+///
+/// The input data is written with [`assign`](U::assign) that checks the data size against the input size
 #[macro_export]
 macro_rules! build_inputs {
     ($($name:ident, $size:expr),+) => {
@@ -65,6 +135,40 @@ macro_rules! build_inputs {
         pub enum U<'a> {
             $($name(&'a mut [f64; $size])),+
         }
+        impl<'a> U<'a> {
+            /// Returns the number of elements of the input
+            pub fn size(&self) -> usize {
+                match self {
+                    $(U::$name(_) => $size),+
+                }
+            }
+            /// Returns the name of the input
+            pub fn name(&self) -> &str {
+                match self {
+                    $(U::$name(_) => stringify!($name)),+
+                }
+            }
+            /// Writes `values` into the input
+            ///
+            /// Returns an error if the number of values does not match the input size
+            pub fn assign(&mut self, values: Vec<f64>) -> Result<(), $crate::DOSIOSError> {
+                if values.len() != self.size() {
+                    return Err($crate::DOSIOSError::Inputs(
+                        format!(
+                            "controller input {} expects {} values, found {}",
+                            self.name(),
+                            self.size(),
+                            values.len()
+                        )
+                        .into(),
+                    ));
+                }
+                for (k, v) in values.into_iter().enumerate() {
+                    self[k] = v;
+                }
+                Ok(())
+            }
+        }
         impl<'a> std::ops::Index<usize> for U<'a> {
             type Output = f64;
             fn index(&self, index: usize) -> &Self::Output {
@@ -81,12 +185,46 @@ macro_rules! build_inputs {
             }
         }
     };
-    ($($name:ident, $size:expr,$offset:expr),+) => {
+    ($($name:ident, $size:expr,$subsize:expr,$offset:expr),+) => {
         /// Controller inputs U
         #[derive(Debug)]
         pub enum U<'a> {
             $($name(&'a mut [f64; $size])),+
         }
+        impl<'a> U<'a> {
+            /// Returns the number of elements of the input
+            pub fn size(&self) -> usize {
+                match self {
+                    $(U::$name(_) => $subsize),+
+                }
+            }
+            /// Returns the name of the input
+            pub fn name(&self) -> &str {
+                match self {
+                    $(U::$name(_) => stringify!($name)),+
+                }
+            }
+            /// Writes `values` into the input
+            ///
+            /// Returns an error if the number of values does not match the input size
+            pub fn assign(&mut self, values: Vec<f64>) -> Result<(), $crate::DOSIOSError> {
+                if values.len() != self.size() {
+                    return Err($crate::DOSIOSError::Inputs(
+                        format!(
+                            "controller input {} expects {} values, found {}",
+                            self.name(),
+                            self.size(),
+                            values.len()
+                        )
+                        .into(),
+                    ));
+                }
+                for (k, v) in values.into_iter().enumerate() {
+                    self[k] = v;
+                }
+                Ok(())
+            }
+        }
         impl<'a> std::ops::Index<usize> for U<'a> {
             type Output = f64;
             fn index(&self, index: usize) -> &Self::Output {
@@ -201,6 +339,39 @@ macro_rules! build_controller {
                     [<$controller _terminate>]();
                 }
             }
+            fn snapshot(&self) -> $crate::controllers::simulink::SimulinkState {
+                unsafe {
+                    let mut data = vec![0u8; [<$controller _state_size>]()];
+                    [<$controller _state_get>](data.as_mut_ptr());
+                    $crate::controllers::simulink::SimulinkState {
+                        model: stringify!($controller).to_string(),
+                        data,
+                    }
+                }
+            }
+            fn restore(
+                &mut self,
+                state: &$crate::controllers::simulink::SimulinkState,
+            ) -> Result<(), $crate::controllers::simulink::SimulinkError> {
+                if state.model != stringify!($controller) {
+                    return Err($crate::controllers::simulink::SimulinkError::Model {
+                        expected: stringify!($controller).to_string(),
+                        found: state.model.clone(),
+                    });
+                }
+                unsafe {
+                    let size = [<$controller _state_size>]();
+                    if state.data.len() != size {
+                        return Err($crate::controllers::simulink::SimulinkError::StateSize {
+                            model: state.model.clone(),
+                            expected: size,
+                            found: state.data.len(),
+                        });
+                    }
+                    [<$controller _state_set>](state.data.as_ptr());
+                }
+                Ok(())
+            }
         }
         }
         impl<'a> Drop for Controller<'a> {
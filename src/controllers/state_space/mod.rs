@@ -61,6 +61,7 @@ pub enum StateSpaceError {
     FemOutputs(Tags),
     MissingArguments(String),
     SamplingFrequency,
+    SamplingRate { expected: f64, found: f64 },
     MissingIO(IOError<Vec<f64>>),
 }
 impl fmt::Display for StateSpaceError {
@@ -70,6 +71,11 @@ impl fmt::Display for StateSpaceError {
             Self::FemOutputs(t) => write!(f, "no match for {:?} in FEM outputs", t),
             Self::MissingArguments(v) => write!(f, "argument {:?} is missing", v),
             Self::SamplingFrequency => f.write_str("sampling frequency not set"),
+            Self::SamplingRate { expected, found } => write!(
+                f,
+                "inputs sampling rate ({}Hz) does not match the model sampling rate ({}Hz)",
+                found, expected
+            ),
             Self::MissingIO(_) => f.write_str("DOS IO not found"),
         }
     }
@@ -97,6 +103,7 @@ pub struct DiscreteStateSpace {
     sampling: Option<f64>,
     fem: Option<Box<fem::FEM>>,
     u: StateSpaceIO,
+    u_rates: Vec<f64>,
     y: StateSpaceIO,
     zeta: Option<f64>,
     eigen_frequencies: Option<Vec<(usize, f64)>>,
@@ -171,7 +178,12 @@ impl DiscreteStateSpace {
         Self { u, ..self }
     }
     /// Sets the model inputs based on the outputs of another component
-    pub fn inputs_from(self, element: &dyn IOTags) -> Self {
+    ///
+    /// If the component outputs sampling rate is known, it is checked against the model sampling rate when the model is built
+    pub fn inputs_from(mut self, element: &dyn IOTags) -> Self {
+        if let Some(rate) = element.outputs_rate() {
+            self.u_rates.push(rate);
+        }
         self.inputs(element.outputs_tags())
     }
     /// Sets the model outputs from a vector of [IO]
@@ -286,6 +298,16 @@ impl DiscreteStateSpace {
             Err(StateSpaceError::MissingArguments("sampling".to_owned())),
             |x| Ok(1f64 / x),
         )?;
+        if let Some(found) = self
+            .u_rates
+            .iter()
+            .find(|rate| (*rate * tau - 1f64).abs() > 1e-6)
+        {
+            return Err(StateSpaceError::SamplingRate {
+                expected: 1f64 / tau,
+                found: *found,
+            });
+        }
         let mut fem = self
             .fem
             .map_or(Err(StateSpaceError::MissingArguments("FEM".to_owned())), Ok)?;
@@ -0,0 +1,164 @@
+//! Summing junction
+//!
+//! A [`SummingJunction`] adds up several inputs of the same size, each multiplied by a gain, into a single output $`y=\sum_i g_i u_i`$.
+//!
+//! The output tag may be the tag of one of the inputs: the junction then supersedes the producer of that input in a [`Model`](crate::model::Model),
+//! so the other consumers of the tag receive the sum instead of the original signal.
+//!
+//! # Example
+//! Adding the M1 CG controller forces to the wind loads on the M1 segments and subtracting them from the M1 cells:
+//! ```no_run
+//! use dos::{controllers::SummingJunction, io::jar};
+//!
+//! let m1_segments = SummingJunction::new(jar::OSSM1Lcl6F::new())
+//!     .plus(jar::OSSM1Lcl6F::new())
+//!     .plus(jar::M1CGFM::new());
+//! let m1_cells = SummingJunction::new(jar::OSSCellLcl6F::new())
+//!     .plus(jar::OSSCellLcl6F::new())
+//!     .minus(jar::M1CGFM::new());
+//! ```
+
+use crate::{io::Tags, DOSIOSError, IOTags, DOS, IO};
+
+/// Summing junction component
+#[derive(Debug, Clone)]
+pub struct SummingJunction {
+    inputs: Vec<(Tags, f64)>,
+    output: Tags,
+    u: Vec<Vec<f64>>,
+    y: Vec<f64>,
+}
+impl SummingJunction {
+    /// Creates a summing junction with the `output` tag
+    pub fn new(output: Tags) -> Self {
+        Self {
+            inputs: vec![],
+            output,
+            u: vec![],
+            y: vec![],
+        }
+    }
+    /// Adds the input `tag` multiplied by `gain`
+    pub fn gain(mut self, tag: Tags, gain: f64) -> Self {
+        self.inputs.push((tag, gain));
+        self
+    }
+    /// Adds the input `tag`
+    pub fn plus(self, tag: Tags) -> Self {
+        self.gain(tag, 1.)
+    }
+    /// Subtracts the input `tag`
+    pub fn minus(self, tag: Tags) -> Self {
+        self.gain(tag, -1.)
+    }
+}
+impl Iterator for SummingJunction {
+    type Item = ();
+    fn next(&mut self) -> Option<Self::Item> {
+        let n = self.u.first().map_or(0, |u| u.len());
+        self.y = vec![0f64; n];
+        for (u, (_, gain)) in self.u.iter().zip(self.inputs.iter()) {
+            self.y.iter_mut().zip(u).for_each(|(y, u)| *y += gain * u);
+        }
+        Some(())
+    }
+}
+impl IOTags for SummingJunction {
+    fn outputs_tags(&self) -> Vec<Tags> {
+        vec![self.output.clone()]
+    }
+    fn inputs_tags(&self) -> Vec<Tags> {
+        self.inputs.iter().map(|(tag, _)| tag.clone()).collect()
+    }
+}
+impl DOS for SummingJunction {
+    fn inputs(&mut self, data: Vec<IO<Vec<f64>>>) -> Result<&mut Self, DOSIOSError> {
+        self.u = self
+            .inputs
+            .iter()
+            .map(|(tag, _)| {
+                data.iter()
+                    .find(|io| **io == *tag)
+                    .and_then(Option::<Vec<f64>>::from)
+                    .ok_or_else(|| {
+                        DOSIOSError::Inputs(
+                            format!("summing junction input {:?} not found", tag).into(),
+                        )
+                    })
+            })
+            .collect::<Result<Vec<Vec<f64>>, DOSIOSError>>()?;
+        let n = self.u.first().map_or(0, |u| u.len());
+        if self.u.iter().any(|u| u.len() != n) {
+            return Err(DOSIOSError::Inputs(
+                "summing junction inputs must have the same size".into(),
+            ));
+        }
+        Ok(self)
+    }
+    fn outputs(&mut self) -> Option<Vec<IO<Vec<f64>>>> {
+        Some(vec![(&self.output, self.y.clone()).into()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{io::jar, model::Model, telltale::replay::Replay, DataLogging};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn summing_junction() {
+        let mut junction = SummingJunction::new(jar::OSSCellLcl6F::new())
+            .plus(jar::OSSCellLcl6F::new())
+            .minus(jar::M1CGFM::new())
+            .gain(jar::MCM2Lcl6D::new(), 0.5);
+        assert_eq!(junction.inputs_tags().len(), 3);
+        let y = junction
+            .in_step_out(vec![
+                jar::M1CGFM::with(vec![1., 2.]),
+                jar::MCM2Lcl6D::with(vec![4., 4.]),
+                jar::OSSCellLcl6F::with(vec![10., 20.]),
+            ])
+            .unwrap()
+            .unwrap();
+        assert!(y[0] == jar::OSSCellLcl6F::new::<()>());
+        assert_eq!(Option::<Vec<f64>>::from(&y[0]), Some(vec![11., 20.]));
+        assert!(junction
+            .inputs(vec![jar::OSSCellLcl6F::with(vec![1.])])
+            .is_err());
+    }
+
+    #[test]
+    fn summing_junction_model() {
+        let mut entries = BTreeMap::new();
+        entries.insert(
+            "OSSCellLcl6F".to_string(),
+            vec![(0., vec![10.]), (1., vec![20.])],
+        );
+        entries.insert("M1CGFM".to_string(), vec![(0., vec![1.]), (1., vec![2.])]);
+        let mut source = Replay::new(1., entries)
+            .key(jar::OSSCellLcl6F::new())
+            .unwrap()
+            .key(jar::M1CGFM::new())
+            .unwrap();
+        let mut junction = SummingJunction::new(jar::OSSCellLcl6F::new())
+            .plus(jar::OSSCellLcl6F::new())
+            .minus(jar::M1CGFM::new());
+        let mut data = DataLogging::new().key(jar::OSSCellLcl6F::new()).build();
+        let mut simulation = Model::new()
+            .component("data", &mut data)
+            .component("junction", &mut junction)
+            .source("source", &mut source)
+            .build()
+            .unwrap();
+        assert_eq!(
+            simulation.report().order,
+            vec!["source", "junction", "data"]
+        );
+        assert!(simulation.report().unused.is_empty());
+        simulation.run(None).unwrap();
+        drop(simulation);
+        let cells = &data.entry(&jar::OSSCellLcl6F::new()).unwrap().samples;
+        assert_eq!(cells, &vec![(0, vec![9.]), (1, vec![18.])]);
+    }
+}
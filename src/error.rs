@@ -1,6 +1,6 @@
 use super::{
-    controllers::state_space::StateSpaceError, io::IOError, telltale::TellTaleError,
-    wind_loads::WindLoadsError, DOSIOSError
+    config::ConfigError, controllers::state_space::StateSpaceError, io::IOError, model::ModelError,
+    telltale::TellTaleError, wind_loads::WindLoadsError, DOSIOSError,
 };
 use fem::fem::FEMError;
 use std::{fmt, io};
@@ -13,10 +13,13 @@ pub enum DOSError {
     Step,
     File(io::Error),
     Pickle(serde_pickle::Error),
+    Json(serde_json::Error),
     IO(IOError<Vec<f64>>),
     WindLoads(WindLoadsError),
     StateSpace(StateSpaceError),
     TellTale(TellTaleError),
+    Model(ModelError),
+    Config(ConfigError),
     FEM(FEMError),
     Other(String),
     DOSIOS(DOSIOSError),
@@ -34,6 +37,12 @@ impl From<serde_pickle::Error> for DOSError {
     }
 }
 
+impl From<serde_json::Error> for DOSError {
+    fn from(e: serde_json::Error) -> DOSError {
+        DOSError::Json(e)
+    }
+}
+
 impl From<IOError<Vec<f64>>> for DOSError {
     fn from(e: IOError<Vec<f64>>) -> Self {
         Self::IO(e)
@@ -58,6 +67,18 @@ impl From<TellTaleError> for DOSError {
     }
 }
 
+impl From<ModelError> for DOSError {
+    fn from(e: ModelError) -> Self {
+        Self::Model(e)
+    }
+}
+
+impl From<ConfigError> for DOSError {
+    fn from(e: ConfigError) -> Self {
+        Self::Config(e)
+    }
+}
+
 impl From<FEMError> for DOSError {
     fn from(e: FEMError) -> Self {
         Self::FEM(e)
@@ -91,10 +112,13 @@ impl fmt::Display for DOSError {
             //Component(component) => component.fmt(f),
             File(error) => error.fmt(f),
             Pickle(error) => error.fmt(f),
+            Json(error) => error.fmt(f),
             IO(error) => error.fmt(f),
             WindLoads(error) => error.fmt(f),
             StateSpace(error) => error.fmt(f),
             TellTale(error) => error.fmt(f),
+            Model(error) => error.fmt(f),
+            Config(error) => error.fmt(f),
             FEM(error) => error.fmt(f),
             Other(error) => error.fmt(f),
             DOSIOS(error) => error.fmt(f),
@@ -112,10 +136,13 @@ impl std::error::Error for DOSError {
         match self {
             Self::File(source) => Some(source),
             Self::Pickle(source) => Some(source),
+            Self::Json(source) => Some(source),
             Self::IO(source) => Some(source),
             Self::WindLoads(source) => Some(source),
             Self::StateSpace(source) => Some(source),
             Self::TellTale(source) => Some(source),
+            Self::Model(source) => Some(source),
+            Self::Config(source) => Some(source),
             Self::FEM(source) => Some(source),
             Self::DOSIOS(source) => Some(source),
             _ => None,
@@ -16,9 +16,14 @@
 //! Each component structure must implement the [`Iterator`] and the [`DOS`] traits.
 //! The [`next`](core::iter::Iterator::next) method of the [`Iterator`] trait is used to update the state of the component at each time step.
 //! The [`inputs`](crate::DOS::inputs) method of the [`DOS`] trait passes inputs data to the components whereas the [`outputs`](crate::DOS::outputs) method returns the component outputs.
+//!
+//! The components are either connected manually or registered into a [`Model`](crate::model::Model) that connects them automatically according to their [`IOTags`].
+//! A whole simulation can also be described in a configuration file (see [`config`]) and run with the `dos-run` binary.
 
+pub mod config;
 pub mod controllers;
 pub mod error;
+pub mod model;
 pub mod telltale;
 pub mod wind_loads;
 
@@ -31,7 +36,7 @@ pub use wind_loads::{WindLoading, WindLoads};
 
 pub mod match_io;
 pub mod io {
-    pub use super::match_io::{MatchFEM, MatchWindLoads};
+    pub use super::match_io::{io_name, io_tag, MatchFEM, MatchWindLoads};
     pub use dosio::io::*;
 }
 pub use dosio::{DOSIOSError, DOS};
@@ -43,4 +48,8 @@ pub trait IOTags {
     fn outputs_tags(&self) -> Vec<IO<()>>;
     /// Return the list of inputs
     fn inputs_tags(&self) -> Vec<IO<()>>;
+    /// Return the sampling rate in Hz of the outputs, if any
+    fn outputs_rate(&self) -> Option<f64> {
+        None
+    }
 }
@@ -2,9 +2,79 @@
 //!
 //! Provides the definitions for all the inputs and outputs used by DOS
 
-use super::{io::IO, wind_loads};
+use super::{
+    io::{jar, Tags, IO},
+    wind_loads,
+};
 use core::fmt::Debug;
 
+/// Returns the name of the [`IO`] variant
+pub fn io_name<T>(io: &IO<T>) -> String {
+    let tag: IO<()> = io.into();
+    format!("{:?}", tag)
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+macro_rules! io_tag {
+    ($($variant:ident),+) => {
+        /// Returns the [`IO`] tag of the variant named `name`, the reverse of [`io_name`]
+        pub fn io_tag(name: &str) -> Option<Tags> {
+            match name {
+                $(stringify!($variant) => Some(jar::$variant::new()),)+
+                _ => None,
+            }
+        }
+        /// Returns the names and [`IO`] tags of all the variants known to [`io_tag`]
+        #[cfg(test)]
+        fn io_tags() -> Vec<(&'static str, Tags)> {
+            vec![$((stringify!($variant), jar::$variant::new()),)+]
+        }
+    };
+}
+io_tag!(
+    MCM2RB6F,
+    MCASMCOG6F,
+    MCM2TE6F,
+    OSSTopEnd6F,
+    OSSTruss6F,
+    OSSGIR6F,
+    OSSCRING6F,
+    OSSCellLcl6F,
+    OSSM1Lcl6F,
+    MCM2Lcl6F,
+    OSSAzDriveF,
+    OSSElDriveF,
+    OSSGIRDriveF,
+    OSSHarpointDeltaF,
+    OSSAzDriveTorque,
+    OSSElDriveTorque,
+    OSSRotDriveTorque,
+    OSSM1FansLcl6F,
+    OSSPayloads6F,
+    OSSAzDriveD,
+    OSSElDriveD,
+    OSSGIRDriveD,
+    OSSM1Lcl,
+    MCM2Lcl6D,
+    OSSHardpointD,
+    OSSAzEncoderAngle,
+    OSSElEncoderAngle,
+    OSSRotEncoderAngle,
+    MCM2RB6D,
+    MCASMCOG6D,
+    MCM2TE6D,
+    OSSM1FansLcl6D,
+    OSSPayloads6D,
+    MountCmd,
+    MountSP,
+    M1HPCmd,
+    M1HPLC,
+    M1CGFM
+);
+
 pub trait MatchFEM {
     fn match_fem_inputs(&self, fem_inputs: &fem::fem_io::Inputs) -> Option<Vec<fem::IO>>;
     fn match_fem_outputs(&self, fem_outputs: &fem::fem_io::Outputs) -> Option<Vec<fem::IO>>;
@@ -109,3 +179,16 @@ io_match_wind_loads!(
     OSSM1Lcl6F,
     MCM2Lcl6F
 );
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn io_tag_round_trip() {
+        for (name, tag) in io_tags() {
+            assert_eq!(io_name(&tag), name);
+            assert!(io_tag(&io_name(&tag)) == Some(tag), "{}", name);
+        }
+    }
+}
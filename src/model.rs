@@ -0,0 +1,524 @@
+//! Simulation model
+//!
+//! A simulation [`Model`] is a set of components that are connected automatically by matching the outputs of each component ([`IOTags::outputs_tags`]) to the inputs of the other components ([`IOTags::inputs_tags`]).
+//! The components are either:
+//!  - sources, like [`WindLoading`](crate::WindLoading), that only have outputs and that set the length of the simulation: the simulation ends when one of the sources has no more outputs,
+//!  - or components that implement [`Iterator`], like [`DiscreteModalSolver`](crate::controllers::state_space::DiscreteModalSolver) or [`TellTale`](crate::telltale::TellTale), that are updated with [`DOS::inputs`], [`DOS::step`] and [`DOS::outputs`] in that order.
+//!
+//! A component may be updated every `n` time steps only, its outputs are then held between updates.
+//!
+//! At each time step, the components are updated in dependency order: the producers of some inputs are updated before the consumers of these inputs.
+//! Feedback loops are broken by updating one of the components of the loop with the outputs of the previous time step, so an initial value must be given for these inputs with [`Model::initial`].
+//! An initial value given to an input without producer makes it a constant input.
+//!
+//! An input must have a single producer, except if one of the producers also consumes the input, like a [`SummingJunction`](crate::controllers::SummingJunction) adding some signals to the input:
+//! this producer supersedes the others for all the other consumers of the input.
+//!
+//! The [`Simulation`] built from a [`Model`] gives a [`ModelReport`] of the update order, of the delayed inputs, of the inputs without producer and of the unused outputs.
+//!
+//! # Example
+//! ```no_run
+//! use dos::{
+//!     controllers::state_space::DiscreteStateSpace, io::jar, model::Model, DataLogging,
+//!     WindLoads,
+//! };
+//! use fem::FEM;
+//!
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let mut wind_loading = WindLoads::from_pickle("wind_loads.pkl")?
+//!         .truss()?
+//!         .build()?;
+//!     let mut fem = DiscreteStateSpace::from(FEM::from_pickle("fem.pkl")?)
+//!         .sampling(1e3)
+//!         .inputs_from(&wind_loading)
+//!         .outputs(vec![jar::OSSM1Lcl::new()])
+//!         .build()?;
+//!     let mut data = DataLogging::new()
+//!         .sampling_rate(1e3)
+//!         .key(jar::OSSM1Lcl::new())
+//!         .build();
+//!     let mut simulation = Model::new()
+//!         .source("wind loads", &mut wind_loading)
+//!         .component("FEM", &mut fem)
+//!         .component("data logging", &mut data)
+//!         .build()?;
+//!     println!("{}", simulation.report());
+//!     simulation.run(None)?;
+//!     Ok(())
+//! }
+//! ```
+
+use crate::{
+    io::{io_name, Tags},
+    DOSIOSError, IOTags, DOS, IO,
+};
+use std::{collections::HashMap, fmt};
+
+#[derive(Debug)]
+pub enum ModelError {
+    Unconnected(String),
+    Producers(String),
+    Missing(String),
+    Component(String, DOSIOSError),
+}
+impl fmt::Display for ModelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unconnected(e) => write!(f, "inputs without producer nor initial value: {}", e),
+            Self::Producers(e) => write!(f, "inputs with several producers: {}", e),
+            Self::Missing(e) => write!(f, "missing input: {}", e),
+            Self::Component(name, e) => write!(f, "component {} failed: {}", name, e),
+        }
+    }
+}
+impl std::error::Error for ModelError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Component(_, source) => Some(source),
+            _ => None,
+        }
+    }
+}
+type Result<T> = std::result::Result<T, ModelError>;
+
+/// Type erased simulation component
+trait Node {
+    fn inputs_tags(&self) -> Vec<Tags>;
+    fn outputs_tags(&self) -> Vec<Tags>;
+    fn is_source(&self) -> bool;
+    fn update(
+        &mut self,
+        data: Vec<IO<Vec<f64>>>,
+    ) -> std::result::Result<Option<Vec<IO<Vec<f64>>>>, DOSIOSError>;
+}
+struct Source<'a, T>(&'a mut T);
+impl<'a, T: DOS + IOTags> Node for Source<'a, T> {
+    fn inputs_tags(&self) -> Vec<Tags> {
+        vec![]
+    }
+    fn outputs_tags(&self) -> Vec<Tags> {
+        self.0.outputs_tags()
+    }
+    fn is_source(&self) -> bool {
+        true
+    }
+    fn update(
+        &mut self,
+        _: Vec<IO<Vec<f64>>>,
+    ) -> std::result::Result<Option<Vec<IO<Vec<f64>>>>, DOSIOSError> {
+        Ok(self.0.outputs())
+    }
+}
+struct Component<'a, T>(&'a mut T);
+impl<'a, T: DOS + IOTags + Iterator> Node for Component<'a, T> {
+    fn inputs_tags(&self) -> Vec<Tags> {
+        self.0.inputs_tags()
+    }
+    fn outputs_tags(&self) -> Vec<Tags> {
+        self.0.outputs_tags()
+    }
+    fn is_source(&self) -> bool {
+        false
+    }
+    fn update(
+        &mut self,
+        data: Vec<IO<Vec<f64>>>,
+    ) -> std::result::Result<Option<Vec<IO<Vec<f64>>>>, DOSIOSError> {
+        if !data.is_empty() {
+            self.0.inputs(data)?;
+        }
+        Ok(self.0.step()?.outputs())
+    }
+}
+
+struct Entry<'a> {
+    name: String,
+    decimation: usize,
+    node: Box<dyn Node + 'a>,
+}
+
+/// Simulation model builder
+#[derive(Default)]
+pub struct Model<'a> {
+    entries: Vec<Entry<'a>>,
+    initial: Vec<IO<Vec<f64>>>,
+}
+impl<'a> Model<'a> {
+    /// Creates an empty model
+    pub fn new() -> Self {
+        Default::default()
+    }
+    fn entry(mut self, name: &str, decimation: usize, node: Box<dyn Node + 'a>) -> Self {
+        assert!(
+            decimation > 0,
+            "the decimation factor must be greater than 0"
+        );
+        self.entries.push(Entry {
+            name: name.to_string(),
+            decimation,
+            node,
+        });
+        self
+    }
+    /// Adds a source named `name`
+    pub fn source<T: DOS + IOTags>(self, name: &str, source: &'a mut T) -> Self {
+        self.entry(name, 1, Box::new(Source(source)))
+    }
+    /// Adds a component named `name` updated at every time step
+    pub fn component<T: DOS + IOTags + Iterator>(self, name: &str, component: &'a mut T) -> Self {
+        self.decimated_component(name, component, 1)
+    }
+    /// Adds a component named `name` updated every `decimation` time steps
+    pub fn decimated_component<T: DOS + IOTags + Iterator>(
+        self,
+        name: &str,
+        component: &'a mut T,
+        decimation: usize,
+    ) -> Self {
+        self.entry(name, decimation, Box::new(Component(component)))
+    }
+    /// Sets the initial value of an input
+    pub fn initial(mut self, value: IO<Vec<f64>>) -> Self {
+        self.initial.push(value);
+        self
+    }
+    /// Connects the components and sorts them in dependency order
+    pub fn build(self) -> Result<Simulation<'a>> {
+        let Model { entries, initial } = self;
+        let n = entries.len();
+        let inputs: Vec<Vec<Tags>> = entries.iter().map(|e| e.node.inputs_tags()).collect();
+        let outputs: Vec<Vec<Tags>> = entries.iter().map(|e| e.node.outputs_tags()).collect();
+        let initial: HashMap<String, IO<Vec<f64>>> =
+            initial.into_iter().map(|io| (io_name(&io), io)).collect();
+        let name = |i: usize| entries[i].name.clone();
+
+        // producer of each input, a producer that also consumes the input tag supersedes the other producers
+        let mut producers: Vec<Vec<Option<usize>>> = vec![];
+        let mut several = vec![];
+        for (i, tags) in inputs.iter().enumerate() {
+            producers.push(
+                tags.iter()
+                    .map(|tag| {
+                        let mut found: Vec<usize> = (0..n)
+                            .filter(|&j| j != i && outputs[j].contains(tag))
+                            .collect();
+                        if found.len() > 1 {
+                            let superseding: Vec<usize> = found
+                                .iter()
+                                .cloned()
+                                .filter(|&j| inputs[j].contains(tag))
+                                .collect();
+                            if superseding.len() == 1 {
+                                found = superseding;
+                            }
+                        }
+                        if found.len() > 1 {
+                            several.push(format!(
+                                "{}/{} ({})",
+                                name(i),
+                                io_name(tag),
+                                found
+                                    .iter()
+                                    .map(|&j| name(j))
+                                    .collect::<Vec<_>>()
+                                    .join(", ")
+                            ));
+                        }
+                        found.first().cloned()
+                    })
+                    .collect(),
+            );
+        }
+        if !several.is_empty() {
+            return Err(ModelError::Producers(several.join("; ")));
+        }
+        let mut report = ModelReport::default();
+        for (i, tags) in inputs.iter().enumerate() {
+            for (tag, producer) in tags.iter().zip(producers[i].iter()) {
+                if producer.is_none() {
+                    report.unconnected.push((name(i), io_name(tag)));
+                }
+            }
+        }
+        for (j, tags) in outputs.iter().enumerate() {
+            for tag in tags {
+                if !inputs.iter().zip(producers.iter()).any(|(x, p)| {
+                    x.iter()
+                        .zip(p.iter())
+                        .any(|(x, p)| *p == Some(j) && x == tag)
+                }) {
+                    report.unused.push((name(j), io_name(tag)));
+                }
+            }
+        }
+
+        // dependency order, feedback loops are broken at the first registered component of the loop
+        let mut placed = vec![false; n];
+        let mut order = vec![];
+        while order.len() < n {
+            let ready =
+                (0..n).find(|&i| !placed[i] && producers[i].iter().flatten().all(|&j| placed[j]));
+            let i = match ready {
+                Some(i) => i,
+                None => {
+                    let i = (0..n)
+                        .find(|&i| !placed[i] && on_cycle(i, &producers, &placed))
+                        .unwrap_or_else(|| (0..n).find(|&i| !placed[i]).unwrap());
+                    for (tag, producer) in inputs[i].iter().zip(producers[i].iter()) {
+                        if let Some(j) = producer.filter(|&j| !placed[j]) {
+                            report.delayed.push((name(i), io_name(tag), name(j)));
+                        }
+                    }
+                    i
+                }
+            };
+            placed[i] = true;
+            order.push(i);
+        }
+        report.order = order.iter().map(|&i| name(i)).collect();
+
+        let missing: Vec<String> = report
+            .unconnected
+            .iter()
+            .map(|(c, tag)| (c, tag))
+            .chain(report.delayed.iter().map(|(c, tag, _)| (c, tag)))
+            .filter(|(_, tag)| !initial.contains_key(*tag))
+            .map(|(c, tag)| format!("{}/{}", c, tag))
+            .collect();
+        if !missing.is_empty() {
+            return Err(ModelError::Unconnected(missing.join(", ")));
+        }
+
+        Ok(Simulation {
+            entries,
+            inputs: inputs
+                .into_iter()
+                .zip(producers)
+                .map(|(tags, p)| tags.into_iter().zip(p).collect())
+                .collect(),
+            order,
+            values: HashMap::new(),
+            initial,
+            report,
+            step: 0,
+        })
+    }
+}
+/// Returns true if the component `i` is on a feedback loop of the components that are not placed yet
+fn on_cycle(i: usize, producers: &[Vec<Option<usize>>], placed: &[bool]) -> bool {
+    let mut visited = vec![false; producers.len()];
+    let mut stack: Vec<usize> = producers[i].iter().flatten().cloned().collect();
+    while let Some(j) = stack.pop() {
+        if j == i {
+            return true;
+        }
+        if placed[j] || visited[j] {
+            continue;
+        }
+        visited[j] = true;
+        stack.extend(producers[j].iter().flatten());
+    }
+    false
+}
+
+/// Simulation model connections report
+#[derive(Debug, Clone, Default)]
+pub struct ModelReport {
+    /// components update order
+    pub order: Vec<String>,
+    /// inputs without producer: (component, input)
+    pub unconnected: Vec<(String, String)>,
+    /// outputs without consumer: (component, output)
+    pub unused: Vec<(String, String)>,
+    /// inputs from the previous time step: (component, input, producer)
+    pub delayed: Vec<(String, String, String)>,
+}
+impl fmt::Display for ModelReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Update order: {}", self.order.join(" -> "))?;
+        for (c, tag, p) in &self.delayed {
+            writeln!(f, " - delayed input: {}/{} from {}", c, tag, p)?;
+        }
+        for (c, tag) in &self.unconnected {
+            writeln!(f, " - unconnected input: {}/{}", c, tag)?;
+        }
+        for (c, tag) in &self.unused {
+            writeln!(f, " - unused output: {}/{}", c, tag)?;
+        }
+        Ok(())
+    }
+}
+
+/// Simulation of a [`Model`]
+pub struct Simulation<'a> {
+    entries: Vec<Entry<'a>>,
+    /// inputs and producers
+    inputs: Vec<Vec<(Tags, Option<usize>)>>,
+    order: Vec<usize>,
+    /// latest outputs of each component
+    values: HashMap<(usize, String), IO<Vec<f64>>>,
+    initial: HashMap<String, IO<Vec<f64>>>,
+    report: ModelReport,
+    step: usize,
+}
+impl<'a> Simulation<'a> {
+    /// Returns the model connections report
+    pub fn report(&self) -> &ModelReport {
+        &self.report
+    }
+    /// Returns the number of time steps so far
+    pub fn n_step(&self) -> usize {
+        self.step
+    }
+    /// Returns the latest value of an output of the component named `component`
+    pub fn value(&self, component: &str, tag: &Tags) -> Option<&IO<Vec<f64>>> {
+        let i = self.entries.iter().position(|e| e.name == component)?;
+        self.values.get(&(i, io_name(tag)))
+    }
+    /// Updates the components for one time step, returns false if a source has no more outputs
+    pub fn step(&mut self) -> Result<bool> {
+        for &i in &self.order {
+            // step index within the component update period
+            let phase = self.step % self.entries[i].decimation;
+            if phase != 0 {
+                continue;
+            }
+            let name = &self.entries[i].name;
+            let data = self.inputs[i]
+                .iter()
+                .map(|(tag, producer)| {
+                    let tag_name = io_name(tag);
+                    producer
+                        .and_then(|j| self.values.get(&(j, tag_name.clone())))
+                        .or_else(|| self.initial.get(&tag_name))
+                        .cloned()
+                        .ok_or_else(|| ModelError::Missing(format!("{}/{}", name, io_name(tag))))
+                })
+                .collect::<Result<Vec<IO<Vec<f64>>>>>()?;
+            let entry = &mut self.entries[i];
+            match entry
+                .node
+                .update(data)
+                .map_err(|e| ModelError::Component(entry.name.clone(), e))?
+            {
+                Some(outputs) => {
+                    for io in outputs {
+                        self.values.insert((i, io_name(&io)), io);
+                    }
+                }
+                None if entry.node.is_source() => return Ok(false),
+                None => (),
+            }
+        }
+        self.step += 1;
+        Ok(true)
+    }
+    /// Runs the simulation until a source has no more outputs or for at most `n_step` time steps, returns the number of time steps
+    pub fn run(&mut self, n_step: Option<usize>) -> Result<usize> {
+        let start = self.step;
+        while n_step.iter().all(|&n| self.step - start < n) && self.step()? {}
+        Ok(self.step - start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{io::jar, telltale::replay::Replay, DataLogging};
+    use std::collections::BTreeMap;
+
+    /// Sums the first value of the inputs times the gain
+    struct Gain {
+        inputs: Vec<Tags>,
+        output: Tags,
+        gain: f64,
+        u: f64,
+        y: f64,
+    }
+    impl Iterator for Gain {
+        type Item = ();
+        fn next(&mut self) -> Option<()> {
+            self.y = self.gain * self.u;
+            Some(())
+        }
+    }
+    impl IOTags for Gain {
+        fn outputs_tags(&self) -> Vec<Tags> {
+            vec![self.output.clone()]
+        }
+        fn inputs_tags(&self) -> Vec<Tags> {
+            self.inputs.clone()
+        }
+    }
+    impl DOS for Gain {
+        fn inputs(
+            &mut self,
+            data: Vec<IO<Vec<f64>>>,
+        ) -> std::result::Result<&mut Self, DOSIOSError> {
+            self.u = data
+                .iter()
+                .filter_map(|x| Option::<Vec<f64>>::from(x).map(|x| x[0]))
+                .sum();
+            Ok(self)
+        }
+        fn outputs(&mut self) -> Option<Vec<IO<Vec<f64>>>> {
+            Some(vec![(&self.output, vec![self.y]).into()])
+        }
+    }
+
+    #[test]
+    fn feedback_loop() {
+        let mut entries = BTreeMap::new();
+        entries.insert(
+            "OSSTruss6F".to_string(),
+            (0..3).map(|k| (k as f64, vec![k as f64])).collect(),
+        );
+        let mut source = Replay::new(1., entries)
+            .key(jar::OSSTruss6F::new())
+            .unwrap();
+        let gain = |inputs, output, gain| Gain {
+            inputs,
+            output,
+            gain,
+            u: 0.,
+            y: 0.,
+        };
+        let mut a = gain(
+            vec![jar::OSSTruss6F::new(), jar::MCM2Lcl6D::new()],
+            jar::OSSM1Lcl::new(),
+            1.,
+        );
+        let mut b = gain(vec![jar::OSSM1Lcl::new()], jar::MCM2Lcl6D::new(), 0.5);
+        let mut data = DataLogging::new().key(jar::OSSM1Lcl::new()).build();
+        assert!(Model::new()
+            .component("B", &mut b)
+            .component("A", &mut a)
+            .build()
+            .is_err());
+        let mut simulation = Model::new()
+            .component("data", &mut data)
+            .component("B", &mut b)
+            .component("A", &mut a)
+            .source("source", &mut source)
+            .initial(jar::OSSM1Lcl::with(vec![0.]))
+            .build()
+            .unwrap();
+        let report = simulation.report();
+        assert_eq!(report.order, vec!["source", "B", "A", "data"]);
+        assert_eq!(
+            report.delayed,
+            vec![("B".to_string(), "OSSM1Lcl".to_string(), "A".to_string())]
+        );
+        assert!(report.unconnected.is_empty() && report.unused.is_empty());
+        assert_eq!(simulation.run(None).unwrap(), 3);
+        drop(simulation);
+        let m1: Vec<f64> = data
+            .entry(&jar::OSSM1Lcl::new())
+            .unwrap()
+            .samples
+            .iter()
+            .map(|(_, x)| x[0])
+            .collect();
+        assert_eq!(m1, vec![0., 1., 2.5]);
+    }
+}
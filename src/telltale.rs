@@ -1,27 +1,95 @@
-use crate::io::IO;
-use std::{collections::BTreeMap, fmt};
+use crate::{
+    io::{io_name, Tags, IO},
+    DOSIOSError, IOTags, DOS,
+};
+use std::{collections::HashMap, fmt, path::Path};
+
+pub mod accumulators;
+pub mod replay;
+pub mod streaming;
+use accumulators::{Accumulator, LogStatistics, OnlineStatistics};
+use streaming::LogWriter;
 
 #[derive(Debug)]
 pub enum TellTaleError {
     Step,
     Tale,
+    IO(std::io::Error),
+    Format(String),
+    Results(String),
+    Replay(String),
+    #[cfg(feature = "apache-arrow")]
+    Parquet(parquet::errors::ParquetError),
 }
 impl fmt::Display for TellTaleError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::Step => f.write_str("failed stepping through wind loads"),
             Self::Tale => f.write_str("failed to log data"),
+            Self::IO(e) => write!(f, "failed to access the data log file: {}", e),
+            Self::Format(e) => write!(f, "invalid data log: {}", e),
+            Self::Results(e) => write!(f, "failed to write the statistics: {}", e),
+            Self::Replay(e) => write!(f, "failed to replay the logged data: {}", e),
+            #[cfg(feature = "apache-arrow")]
+            Self::Parquet(e) => write!(f, "failed to write Parquet file: {}", e),
+        }
+    }
+}
+impl From<std::io::Error> for TellTaleError {
+    fn from(e: std::io::Error) -> Self {
+        Self::IO(e)
+    }
+}
+#[cfg(feature = "apache-arrow")]
+impl From<parquet::errors::ParquetError> for TellTaleError {
+    fn from(e: parquet::errors::ParquetError) -> Self {
+        Self::Parquet(e)
+    }
+}
+impl std::error::Error for TellTaleError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::IO(source) => Some(source),
+            #[cfg(feature = "apache-arrow")]
+            Self::Parquet(source) => Some(source),
+            _ => None,
         }
     }
 }
-impl std::error::Error for TellTaleError {}
 type Result<T> = std::result::Result<T, TellTaleError>;
 
+/// Logged data of a key
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    /// logged [`IO`]
+    pub key: IO<()>,
+    /// the data are logged every `decimation` time steps
+    pub decimation: usize,
+    /// time step indices and data
+    pub samples: Vec<(usize, Vec<f64>)>,
+}
+impl LogEntry {
+    /// Returns the time series of the entry given the simulation sampling rate in Hz
+    pub fn time_series(&self, sampling_rate: f64) -> TimeSeries {
+        let tau = sampling_rate.recip();
+        self.samples
+            .iter()
+            .map(|(index, data)| (*index as f64 * tau, data.clone()))
+            .collect()
+    }
+}
+
 pub struct TellTale {
     pub sampling_rate: f64,
     pub keys: Vec<IO<()>>,
-    pub entries: BTreeMap<usize, Vec<IO<Vec<f64>>>>,
+    /// logged data, one entry per key
+    pub entries: Vec<LogEntry>,
+    lookup: HashMap<String, usize>,
+    /// online statistics accumulators
+    pub accumulators: Vec<Accumulator>,
+    accumulators_lookup: HashMap<String, usize>,
     index: Option<usize>,
+    writer: Option<LogWriter>,
 }
 impl TellTale {
     pub fn step(&mut self) -> Result<&mut Self>
@@ -32,48 +100,180 @@ impl TellTale {
             .and(Some(self))
             .ok_or_else(|| TellTaleError::Step)
     }
+    /// Logs the data if the [`IO`] is one of the keys and the time step is a multiple of the key decimation factor,
+    /// and adds the data to the [`IO`] statistics if any
+    ///
+    /// Data that do not match any key are ignored
     pub fn log(&mut self, tale: &IO<Vec<f64>>) -> Result<&mut Self> {
-        self.index
-            .and_then(|i| {
-                self.entries.entry(i).or_default().push(tale.clone());
-                Some(())
-            })
-            .ok_or(TellTaleError::Tale)?;
+        let index = self.index.ok_or(TellTaleError::Tale)?;
+        let name = io_name(tale);
+        if let Some(&k) = self.accumulators_lookup.get(&name) {
+            if let Some(data) = Option::<Vec<f64>>::from(tale) {
+                self.accumulators[k].add(&data)?;
+            }
+        }
+        if let Some(&k) = self.lookup.get(&name) {
+            let entry = &mut self.entries[k];
+            if index % entry.decimation == 0 {
+                if let Some(data) = Option::<Vec<f64>>::from(tale) {
+                    entry.samples.push((index, data));
+                }
+            }
+        }
         Ok(self)
     }
-    pub fn time_series(&self, key: IO<()>) -> IO<TimeSeries> {
-        let tau = self.sampling_rate.recip();
-        (
-            &key,
-            self.entries
+    /// Returns the statistics of the keys accumulated so far
+    pub fn statistics(&self) -> LogStatistics {
+        LogStatistics {
+            sampling_rate: self.sampling_rate,
+            keys: self
+                .accumulators
                 .iter()
-                .map(|(index, data)| {
-                    data.iter()
-                        .find_map(|d| if key == *d { d.into() } else { None })
-                        .and_then(|x| Some((*index as f64 * tau, x)))
-                })
+                .map(|x| x.statistics(self.sampling_rate))
                 .collect(),
-        )
-            .into()
+        }
+    }
+    /// Returns the logged data of the `key` [`IO`]
+    pub fn entry(&self, key: &IO<()>) -> Option<&LogEntry> {
+        self.lookup.get(&io_name(key)).map(|&k| &self.entries[k])
+    }
+    /// Writes the buffered entries to the log file, if any, and flushes it
+    ///
+    /// The entries written to the log file are removed from memory and they are read back with [`LogReader`](streaming::LogReader)
+    pub fn flush(&mut self) -> Result<()> {
+        if let Some(writer) = self.writer.as_mut() {
+            let tau = self.sampling_rate.recip();
+            for entry in self.entries.iter_mut() {
+                let name = io_name(&entry.key);
+                for (index, data) in entry.samples.drain(..) {
+                    writer.write(&name, index as f64 * tau, &data)?;
+                }
+            }
+            writer.flush()?;
+        }
+        Ok(())
+    }
+    /// Returns the time series of the `key` [`IO`] held in memory
+    pub fn time_series(&self, key: IO<()>) -> IO<TimeSeries> {
+        let time_series = self
+            .entry(&key)
+            .map(|entry| entry.time_series(self.sampling_rate));
+        (&key, time_series).into()
     }
 }
 pub type TimeSeries = Vec<(f64, Vec<f64>)>;
+#[cfg(feature = "apache-arrow")]
+impl TellTale {
+    /// Writes the logged data into a Parquet file
+    ///
+    /// The file has a `time` column and a column of lists of `f64` per key named after the [`IO`] variant.
+    /// The samples that are not logged at a given time step are written as null lists.
+    pub fn to_parquet<P: AsRef<std::path::Path>>(&self, path: P) -> Result<()> {
+        use crate::wind_loads::apache_arrow;
+        use arrow::array::{ArrayRef, Float64Array};
+        let indices: std::collections::BTreeSet<usize> = self
+            .entries
+            .iter()
+            .flat_map(|entry| entry.samples.iter().map(|(index, _)| *index))
+            .collect();
+        let tau = self.sampling_rate.recip();
+        let mut columns: Vec<(String, ArrayRef)> = vec![(
+            "time".to_string(),
+            std::sync::Arc::new(Float64Array::from(
+                indices
+                    .iter()
+                    .map(|index| *index as f64 * tau)
+                    .collect::<Vec<f64>>(),
+            )),
+        )];
+        for entry in self
+            .entries
+            .iter()
+            .filter(|entry| !entry.samples.is_empty())
+        {
+            let rows = indices.iter().map(|index| {
+                entry
+                    .samples
+                    .binary_search_by_key(index, |(i, _)| *i)
+                    .ok()
+                    .map(|k| entry.samples[k].1.as_slice())
+            });
+            columns.push((io_name(&entry.key), apache_arrow::list_column(rows)));
+        }
+        Ok(apache_arrow::write_parquet(path, columns)?)
+    }
+}
 impl Iterator for TellTale {
     type Item = ();
     fn next(&mut self) -> Option<Self::Item> {
         self.index = self.index.map_or(Some(0), |x| Some(x + 1));
+        let chunk_size = self.writer.as_ref().map(|w| w.chunk_size);
+        if let (Some(index), Some(chunk_size)) = (self.index, chunk_size) {
+            if index > 0 && index % chunk_size == 0 {
+                if let Err(e) = self.flush() {
+                    log::error!("{}", e);
+                    return None;
+                }
+            }
+        }
         Some(())
     }
 }
+/// [`TellTale`] as a simulation sink
+///
+/// The inputs are the registered keys and they are logged at the current time step, the log starts at the first time step if it has not been started with [`TellTale::step`].
+/// A sink has no outputs.
+impl DOS for TellTale {
+    fn inputs(&mut self, data: Vec<IO<Vec<f64>>>) -> std::result::Result<&mut Self, DOSIOSError> {
+        if self.index.is_none() {
+            self.index = Some(0);
+        }
+        for tale in &data {
+            self.log(tale)
+                .map_err(|e| DOSIOSError::Inputs(Box::new(e)))?;
+        }
+        Ok(self)
+    }
+    fn outputs(&mut self) -> Option<Vec<IO<Vec<f64>>>> {
+        None
+    }
+}
+impl IOTags for TellTale {
+    fn outputs_tags(&self) -> Vec<Tags> {
+        vec![]
+    }
+    fn inputs_tags(&self) -> Vec<Tags> {
+        let mut tags = self.keys.clone();
+        for x in &self.accumulators {
+            if !tags.contains(&x.key) {
+                tags.push(x.key.clone());
+            }
+        }
+        tags
+    }
+}
+impl Drop for TellTale {
+    fn drop(&mut self) {
+        if let Err(e) = self.flush() {
+            log::error!("{}", e);
+        }
+    }
+}
 pub struct DataLogging {
     pub sampling_rate: f64,
-    pub keys: Vec<IO<()>>,
+    /// keys and decimation factors
+    pub keys: Vec<(IO<()>, usize)>,
+    /// keys and online statistics options
+    pub statistics: Vec<(IO<()>, OnlineStatistics)>,
+    writer: Option<LogWriter>,
 }
 impl DataLogging {
     pub fn new() -> Self {
         Self {
             sampling_rate: 1f64,
             keys: vec![],
+            statistics: vec![],
+            writer: None,
         }
     }
     pub fn sampling_rate(self, sampling_rate: f64) -> Self {
@@ -82,17 +282,132 @@ impl DataLogging {
             ..self
         }
     }
+    /// Logs the `key` [`IO`] at every time step
     pub fn key(self, key: IO<()>) -> Self {
+        self.decimated_key(key, 1)
+    }
+    /// Logs the `key` [`IO`] every `decimation` time steps
+    ///
+    /// The decimation factor of a key that is already registered is replaced
+    pub fn decimated_key(self, key: IO<()>, decimation: usize) -> Self {
+        assert!(
+            decimation > 0,
+            "the decimation factor must be greater than 0"
+        );
         let mut keys = self.keys;
-        keys.push(key);
+        match keys.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, d)) => *d = decimation,
+            None => keys.push((key, decimation)),
+        }
         Self { keys, ..self }
     }
+    /// Accumulates the statistics of the `key` [`IO`] at every time step, the time series is not logged unless the key is also registered with [`key`](DataLogging::key)
+    ///
+    /// The options of a key that is already registered are replaced
+    pub fn statistics(self, key: IO<()>, options: OnlineStatistics) -> Self {
+        let mut statistics = self.statistics;
+        match statistics.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, o)) => *o = options,
+            None => statistics.push((key, options)),
+        }
+        Self { statistics, ..self }
+    }
+    /// Streams the logged data to the log file at `path`, the data are flushed to the file every `chunk_size` time steps
+    ///
+    /// The log file header is written with the sampling rate, so the sampling rate must be set first
+    pub fn stream<P: AsRef<Path>>(self, path: P, chunk_size: usize) -> Result<Self> {
+        Ok(Self {
+            writer: Some(LogWriter::create(path, self.sampling_rate, chunk_size)?),
+            ..self
+        })
+    }
     pub fn build(self) -> TellTale {
+        let lookup = self
+            .keys
+            .iter()
+            .enumerate()
+            .map(|(k, (key, _))| (io_name(key), k))
+            .collect();
         TellTale {
             sampling_rate: self.sampling_rate,
-            keys: self.keys,
-            entries: BTreeMap::new(),
+            keys: self.keys.iter().map(|(key, _)| key.clone()).collect(),
+            entries: self
+                .keys
+                .into_iter()
+                .map(|(key, decimation)| LogEntry {
+                    key,
+                    decimation,
+                    samples: vec![],
+                })
+                .collect(),
+            lookup,
+            accumulators_lookup: self
+                .statistics
+                .iter()
+                .enumerate()
+                .map(|(k, (key, _))| (io_name(key), k))
+                .collect(),
+            accumulators: self
+                .statistics
+                .into_iter()
+                .map(|(key, options)| Accumulator::new(key, options))
+                .collect(),
             index: None,
+            writer: self.writer,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::jar;
+
+    #[test]
+    fn telltale_sink() {
+        let mut data = DataLogging::new()
+            .sampling_rate(10.)
+            .key(jar::OSSM1Lcl::new())
+            .build();
+        assert!(data.inputs_tags()[0] == jar::OSSM1Lcl::new::<()>());
+        assert!(data.outputs_tags().is_empty());
+        for k in 0..3 {
+            let y = data
+                .in_step_out(vec![
+                    jar::OSSM1Lcl::with(vec![k as f64]),
+                    jar::MCM2RB6D::with(vec![0.]),
+                ])
+                .unwrap();
+            assert!(y.is_none());
+        }
+        let m1 = &data.entry(&jar::OSSM1Lcl::new()).unwrap().samples;
+        assert_eq!(m1, &vec![(0, vec![0.]), (1, vec![1.]), (2, vec![2.])]);
+    }
+
+    #[test]
+    fn decimated_keys() {
+        let mut data = DataLogging::new()
+            .sampling_rate(10.)
+            .key(jar::OSSM1Lcl::new())
+            .decimated_key(jar::MCM2Lcl6D::new(), 3)
+            .build();
+        for k in 0..10 {
+            data.step().unwrap();
+            data.log(&jar::OSSM1Lcl::with(vec![k as f64; 2]))
+                .unwrap()
+                .log(&jar::MCM2Lcl6D::with(vec![-(k as f64)]))
+                .unwrap()
+                .log(&jar::MCM2RB6D::with(vec![0.]))
+                .unwrap();
         }
+        assert_eq!(data.entry(&jar::OSSM1Lcl::new()).unwrap().samples.len(), 10);
+        assert!(data.entry(&jar::MCM2RB6D::new()).is_none());
+        let m2: Option<TimeSeries> = (&data.time_series(jar::MCM2Lcl6D::new())).into();
+        let m2 = m2.unwrap();
+        assert_eq!(m2.len(), 4);
+        assert!((m2[3].0 - 0.9).abs() < 1e-12);
+        assert_eq!(m2[3].1, vec![-9.]);
+        let rb: Option<TimeSeries> = (&data.time_series(jar::MCM2RB6D::new())).into();
+        assert!(rb.is_none());
     }
 }
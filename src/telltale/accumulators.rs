@@ -0,0 +1,338 @@
+//! Online statistics of the logged data
+//!
+//! The statistics of a key are accumulated at each time step instead of logging the time series, so long simulations only keep a compact summary of the data.
+//! For each degree of freedom, the accumulators compute:
+//!  - the mean and the variance with Welford's algorithm, the standard deviation and the RMS,
+//!  - the minimum, the maximum and the peak-to-valley,
+//!  - optionally, the histogram of the values within a given range,
+//!  - optionally, the one-sided power spectral density (PSD) with the Welch method: segments of `n_fft` samples with 50% overlap multiplied by a Hann window.
+//!
+//! The statistics of all the keys are collected into [`LogStatistics`] and written to a JSON or pickle file.
+//!
+//! # Example
+//! ```no_run
+//! use dos::{io::jar, telltale::accumulators::OnlineStatistics, DataLogging};
+//!
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let mut data = DataLogging::new()
+//!         .sampling_rate(1e3)
+//!         .statistics(jar::OSSM1Lcl::new(), OnlineStatistics::new().psd(4096))
+//!         .statistics(
+//!             jar::MCM2RB6D::new(),
+//!             OnlineStatistics::new().histogram(100, -1e-6, 1e-6),
+//!         )
+//!         .build();
+//!     // ... simulation
+//!     data.statistics().to_json("statistics.json")?;
+//!     Ok(())
+//! }
+//! ```
+
+use super::{Result, TellTaleError};
+use crate::{
+    io::{io_name, IO},
+    wind_loads::statistics::Periodogram,
+};
+use serde::Serialize;
+use std::{fs::File, io::BufWriter, path::Path};
+
+/// Online statistics options
+#[derive(Debug, Clone, Default)]
+pub struct OnlineStatistics {
+    histogram: Option<(usize, f64, f64)>,
+    n_fft: Option<usize>,
+}
+impl OnlineStatistics {
+    /// Creates the accumulators of the mean, variance, minimum and maximum
+    pub fn new() -> Self {
+        Default::default()
+    }
+    /// Adds the histogram of the values in the range `[min,max)` with `n_bin` bins of equal width
+    pub fn histogram(self, n_bin: usize, min: f64, max: f64) -> Self {
+        assert!(n_bin > 0, "the number of bins must be greater than 0");
+        assert!(max > min, "the histogram range is empty");
+        Self {
+            histogram: Some((n_bin, min, max)),
+            ..self
+        }
+    }
+    /// Adds the Welch PSD with segments of `n_fft` samples
+    pub fn psd(self, n_fft: usize) -> Self {
+        assert!(n_fft > 1, "n_fft must be greater than 1");
+        Self {
+            n_fft: Some(n_fft),
+            ..self
+        }
+    }
+}
+
+struct Welch {
+    periodogram: Periodogram,
+    n_fft: usize,
+    /// current segment of each degree of freedom
+    segments: Vec<Vec<f64>>,
+    psd: Vec<Vec<f64>>,
+    n_segment: usize,
+}
+
+/// Online statistics accumulator of a key
+pub struct Accumulator {
+    /// accumulated [`IO`]
+    pub key: IO<()>,
+    options: OnlineStatistics,
+    n_sample: usize,
+    mean: Vec<f64>,
+    m2: Vec<f64>,
+    min: Vec<f64>,
+    max: Vec<f64>,
+    counts: Vec<Vec<u64>>,
+    outside: Vec<u64>,
+    welch: Option<Welch>,
+}
+impl Accumulator {
+    /// Creates a new accumulator of the `key` [`IO`]
+    pub fn new(key: IO<()>, options: OnlineStatistics) -> Self {
+        Self {
+            key,
+            welch: options.n_fft.map(|n_fft| Welch {
+                periodogram: Periodogram::new(n_fft),
+                n_fft,
+                segments: vec![],
+                psd: vec![],
+                n_segment: 0,
+            }),
+            options,
+            n_sample: 0,
+            mean: vec![],
+            m2: vec![],
+            min: vec![],
+            max: vec![],
+            counts: vec![],
+            outside: vec![],
+        }
+    }
+    /// Adds a sample
+    pub fn add(&mut self, data: &[f64]) -> Result<()> {
+        let n_dof = data.len();
+        if self.n_sample == 0 {
+            self.mean = vec![0f64; n_dof];
+            self.m2 = vec![0f64; n_dof];
+            self.min = vec![f64::INFINITY; n_dof];
+            self.max = vec![f64::NEG_INFINITY; n_dof];
+            if let Some((n_bin, _, _)) = self.options.histogram {
+                self.counts = vec![vec![0u64; n_bin]; n_dof];
+                self.outside = vec![0u64; n_dof];
+            }
+            if let Some(welch) = self.welch.as_mut() {
+                welch.segments = vec![Vec::with_capacity(welch.n_fft); n_dof];
+                welch.psd = vec![vec![0f64; welch.n_fft / 2 + 1]; n_dof];
+            }
+        } else if n_dof != self.mean.len() {
+            return Err(TellTaleError::Format(format!(
+                "{} has {} values instead of {}",
+                io_name(&self.key),
+                n_dof,
+                self.mean.len()
+            )));
+        }
+        self.n_sample += 1;
+        let n = self.n_sample as f64;
+        for (j, &x) in data.iter().enumerate() {
+            let delta = x - self.mean[j];
+            self.mean[j] += delta / n;
+            self.m2[j] += delta * (x - self.mean[j]);
+            self.min[j] = self.min[j].min(x);
+            self.max[j] = self.max[j].max(x);
+        }
+        if let Some((n_bin, min, max)) = self.options.histogram {
+            let width = (max - min) / n_bin as f64;
+            for (j, &x) in data.iter().enumerate() {
+                if x >= min && x < max {
+                    let k = (((x - min) / width) as usize).min(n_bin - 1);
+                    self.counts[j][k] += 1;
+                } else {
+                    self.outside[j] += 1;
+                }
+            }
+        }
+        if let Some(welch) = self.welch.as_mut() {
+            let hop = (welch.n_fft / 2).max(1);
+            if welch.segments.first().map_or(0, |x| x.len()) + 1 == welch.n_fft {
+                welch.n_segment += 1;
+            }
+            for ((segment, psd), &x) in welch
+                .segments
+                .iter_mut()
+                .zip(welch.psd.iter_mut())
+                .zip(data.iter())
+            {
+                segment.push(x);
+                if segment.len() == welch.n_fft {
+                    welch.periodogram.add(segment, psd);
+                    segment.drain(..hop);
+                }
+            }
+        }
+        Ok(())
+    }
+    /// Returns the statistics given the simulation sampling rate in Hz
+    pub fn statistics(&self, sampling_rate: f64) -> KeyStatistics {
+        let n = self.n_sample.max(1) as f64;
+        let variance: Vec<f64> = self.m2.iter().map(|m2| m2 / n).collect();
+        KeyStatistics {
+            name: io_name(&self.key),
+            n_sample: self.n_sample,
+            rms: self
+                .mean
+                .iter()
+                .zip(variance.iter())
+                .map(|(m, v)| (v + m * m).sqrt())
+                .collect(),
+            std: variance.iter().map(|v| v.sqrt()).collect(),
+            peak_to_valley: self
+                .max
+                .iter()
+                .zip(self.min.iter())
+                .map(|(max, min)| max - min)
+                .collect(),
+            histogram: self.options.histogram.map(|(n_bin, min, max)| Histogram {
+                edges: (0..=n_bin)
+                    .map(|k| min + k as f64 * (max - min) / n_bin as f64)
+                    .collect(),
+                counts: self.counts.clone(),
+                outside: self.outside.clone(),
+            }),
+            psd: self.welch.as_ref().map(|welch| {
+                let mut psd = welch.psd.clone();
+                psd.iter_mut()
+                    .for_each(|psd| welch.periodogram.scale(psd, sampling_rate, welch.n_segment));
+                Psd {
+                    frequency: (0..=welch.n_fft / 2)
+                        .map(|k| k as f64 * sampling_rate / welch.n_fft as f64)
+                        .collect(),
+                    psd,
+                    n_segment: welch.n_segment,
+                }
+            }),
+            mean: self.mean.clone(),
+            variance,
+            min: self.min.clone(),
+            max: self.max.clone(),
+        }
+    }
+}
+
+/// Histogram of the degrees of freedom of a key
+#[derive(Debug, Clone, Serialize)]
+pub struct Histogram {
+    /// bins edges
+    pub edges: Vec<f64>,
+    /// counts: `[dof][bin]`
+    pub counts: Vec<Vec<u64>>,
+    /// number of values outside the histogram range
+    pub outside: Vec<u64>,
+}
+/// Welch PSD of the degrees of freedom of a key
+#[derive(Debug, Clone, Serialize)]
+pub struct Psd {
+    /// frequencies in Hz
+    pub frequency: Vec<f64>,
+    /// PSDs: `[dof][frequency]`
+    pub psd: Vec<Vec<f64>>,
+    /// number of averaged segments
+    pub n_segment: usize,
+}
+/// Statistics of the degrees of freedom of a key
+#[derive(Debug, Clone, Serialize)]
+pub struct KeyStatistics {
+    /// [`IO`] name
+    pub name: String,
+    /// number of samples
+    pub n_sample: usize,
+    pub mean: Vec<f64>,
+    pub variance: Vec<f64>,
+    pub std: Vec<f64>,
+    pub rms: Vec<f64>,
+    pub min: Vec<f64>,
+    pub max: Vec<f64>,
+    pub peak_to_valley: Vec<f64>,
+    pub histogram: Option<Histogram>,
+    pub psd: Option<Psd>,
+}
+
+/// Statistics of the logged data
+#[derive(Debug, Clone, Serialize)]
+pub struct LogStatistics {
+    /// sampling rate in Hz
+    pub sampling_rate: f64,
+    /// statistics of each key
+    pub keys: Vec<KeyStatistics>,
+}
+impl LogStatistics {
+    /// Returns the statistics of the key named `name`
+    pub fn get(&self, name: &str) -> Option<&KeyStatistics> {
+        self.keys.iter().find(|x| x.name == name)
+    }
+    /// Writes the statistics to a pickle file
+    pub fn to_pickle<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let mut f = BufWriter::new(File::create(path)?);
+        serde_pickle::to_writer(&mut f, self, true)
+            .map_err(|e| TellTaleError::Results(e.to_string()))
+    }
+    /// Writes the statistics to a JSON file
+    pub fn to_json<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let f = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(f, self).map_err(|e| TellTaleError::Results(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{io::jar, telltale::accumulators::OnlineStatistics, DataLogging, DOS};
+    use std::f64::consts::PI;
+
+    #[test]
+    fn online_statistics() {
+        let fs = 40.;
+        let mut data = DataLogging::new()
+            .sampling_rate(fs)
+            .statistics(
+                jar::OSSM1Lcl::new(),
+                OnlineStatistics::new().histogram(4, -2., 2.).psd(200),
+            )
+            .build();
+        for k in 0..4000 {
+            // 10Hz sinusoid with 2 units of amplitude and a constant unit
+            let x = 2. * (2. * PI * 10. * k as f64 / fs).sin();
+            data.inputs(vec![jar::OSSM1Lcl::with(vec![x, 1.])]).unwrap();
+            data.next();
+        }
+        assert!(data.entries.is_empty());
+        assert!(data.inputs(vec![jar::OSSM1Lcl::with(vec![0.])]).is_err());
+        let stats = data.statistics();
+        let m1 = stats.get("OSSM1Lcl").unwrap();
+        assert_eq!(m1.n_sample, 4000);
+        assert!(m1.mean[0].abs() < 1e-9);
+        assert!((m1.rms[0] - 2f64.sqrt()).abs() < 1e-9);
+        assert!((m1.peak_to_valley[0] - 4.).abs() < 1e-9);
+        assert_eq!(m1.mean[1], 1.);
+        assert_eq!(m1.std[1], 0.);
+        let histogram = m1.histogram.as_ref().unwrap();
+        assert_eq!(histogram.counts[1], vec![0, 0, 0, 4000]);
+        assert_eq!(
+            histogram.counts[0].iter().sum::<u64>() + histogram.outside[0],
+            4000
+        );
+        // the PSD peaks at 10Hz and integrates to the variance
+        let psd = m1.psd.as_ref().unwrap();
+        assert_eq!(psd.n_segment, 39);
+        let df = psd.frequency[1];
+        let (k_max, _) =
+            psd.psd[0]
+                .iter()
+                .enumerate()
+                .fold((0, 0f64), |a, (k, p)| if *p > a.1 { (k, *p) } else { a });
+        assert!((psd.frequency[k_max] - 10.).abs() < 1e-9);
+        assert!((psd.psd[0].iter().sum::<f64>() * df - 2.).abs() < 1e-2);
+    }
+}
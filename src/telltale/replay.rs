@@ -0,0 +1,224 @@
+//! Logged data replay
+//!
+//! A [`Replay`] is a simulation source that outputs the logged time series of some keys, one time step at a time, at the recorded sampling rate.
+//! The time series are read either from a [`TellTale`](super::TellTale) log file (see [`streaming`](super::streaming)) or from a pickle file of a list of [`IO`] time series.
+//!
+//! The time series of the keys may have different time bases, e.g. if they have been logged with different decimation factors:
+//! the replay starts at the time of the earliest sample and the last sample of each key is held until the next one.
+//! A key without any sample yet is output without data.
+//!
+//! # Example
+//! Replaying the recorded M1 hardpoints displacements:
+//! ```no_run
+//! use dos::{io::jar, telltale::replay::Replay, DOS};
+//!
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let mut replay = Replay::from_log("data.log")?.key(jar::OSSHardpointD::new())?;
+//!     while let Some(hardpoints) = replay.outputs() {
+//!         // ...
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use super::{streaming::LogReader, Result, TellTaleError, TimeSeries};
+use crate::{
+    io::{io_name, Tags, IO},
+    DOSIOSError, IOTags, DOS,
+};
+use serde::Deserialize;
+use std::{collections::BTreeMap, fs::File, io::BufReader, path::Path};
+
+#[derive(Deserialize)]
+struct PickledIO {
+    data: Option<TimeSeries>,
+}
+
+/// Estimates the sampling rate from the smallest time step of the time series
+///
+/// Decimated time series have larger time steps.
+fn sampling_rate(entries: &BTreeMap<String, TimeSeries>) -> Result<f64> {
+    entries
+        .values()
+        .filter(|x| x.len() > 1)
+        .map(|x| (x[x.len() - 1].0 - x[0].0) / (x.len() - 1) as f64)
+        .filter(|dt| dt.is_finite() && *dt > 0f64)
+        .min_by(f64::total_cmp)
+        .map(f64::recip)
+        .ok_or_else(|| TellTaleError::Replay("cannot estimate the sampling rate".to_string()))
+}
+
+/// Replay of logged data
+#[derive(Debug, Clone, Default)]
+pub struct Replay {
+    /// sampling rate in Hz
+    pub sampling_rate: f64,
+    entries: BTreeMap<String, TimeSeries>,
+    /// replayed keys: output tag and logged name
+    keys: Vec<(Tags, String)>,
+    cursors: Vec<Option<usize>>,
+    index: usize,
+}
+impl Replay {
+    /// Creates a replay from the time series of each [`IO`] name and the sampling rate in Hz
+    pub fn new(sampling_rate: f64, entries: BTreeMap<String, TimeSeries>) -> Self {
+        Self {
+            sampling_rate,
+            entries,
+            ..Default::default()
+        }
+    }
+    /// Reads the time series from a [`TellTale`](super::TellTale) log file
+    pub fn from_log<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let log = LogReader::open(path)?;
+        Ok(Self::new(log.sampling_rate, log.entries))
+    }
+    /// Reads the time series from a pickle file of a list of [`IO`] time series, the sampling rate is estimated from the time stamps
+    pub fn from_pickle<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let r = BufReader::new(File::open(path)?);
+        let data: Vec<BTreeMap<String, PickledIO>> =
+            serde_pickle::from_reader(r).map_err(|e| TellTaleError::Replay(e.to_string()))?;
+        let entries: BTreeMap<String, TimeSeries> = data
+            .into_iter()
+            .flatten()
+            .filter_map(|(name, io)| io.data.map(|data| (name, data)))
+            .collect();
+        let sampling_rate = sampling_rate(&entries)?;
+        Ok(Self::new(sampling_rate, entries))
+    }
+    /// Sets the sampling rate in Hz
+    pub fn sampling_rate(self, sampling_rate: f64) -> Self {
+        Self {
+            sampling_rate,
+            ..self
+        }
+    }
+    /// Returns the names of the logged [`IO`]
+    pub fn names(&self) -> Vec<&str> {
+        self.entries.keys().map(|x| x.as_str()).collect()
+    }
+    /// Replays the time series of the `key` [`IO`]
+    pub fn key(self, key: Tags) -> Result<Self> {
+        self.key_as(key.clone(), key)
+    }
+    /// Replays the time series of the `source` [`IO`] as the `target` [`IO`]
+    pub fn key_as(mut self, source: Tags, target: Tags) -> Result<Self> {
+        let name = io_name(&source);
+        if !self.entries.contains_key(&name) {
+            return Err(TellTaleError::Replay(format!("{} is not logged", name)));
+        }
+        self.keys.push((target, name));
+        self.cursors.push(None);
+        Ok(self)
+    }
+    fn series(&self) -> impl Iterator<Item = &TimeSeries> {
+        self.keys.iter().map(move |(_, name)| &self.entries[name])
+    }
+    /// Returns the time of the first sample of the replay
+    pub fn start(&self) -> Option<f64> {
+        self.series()
+            .filter_map(|x| x.first().map(|x| x.0))
+            .fold(None, |t, x| Some(t.map_or(x, |t: f64| t.min(x))))
+    }
+    /// Returns the number of time steps of the replay
+    pub fn n_sample(&self) -> usize {
+        let end = self
+            .series()
+            .filter_map(|x| x.last().map(|x| x.0))
+            .fold(f64::NEG_INFINITY, f64::max);
+        self.start().map_or(0, |t0| {
+            ((end - t0) * self.sampling_rate).round() as usize + 1
+        })
+    }
+    /// Restarts the replay from the first sample
+    pub fn rewind(&mut self) -> &mut Self {
+        self.index = 0;
+        self.cursors.iter_mut().for_each(|c| *c = None);
+        self
+    }
+}
+impl IOTags for Replay {
+    fn outputs_tags(&self) -> Vec<Tags> {
+        self.keys.iter().map(|(tag, _)| tag.clone()).collect()
+    }
+    fn inputs_tags(&self) -> Vec<Tags> {
+        vec![]
+    }
+    fn outputs_rate(&self) -> Option<f64> {
+        Some(self.sampling_rate)
+    }
+}
+impl DOS for Replay {
+    fn inputs(&mut self, _: Vec<IO<Vec<f64>>>) -> std::result::Result<&mut Self, DOSIOSError> {
+        Err(DOSIOSError::Inputs(
+            TellTaleError::Replay("a replay takes no inputs".to_string()).into(),
+        ))
+    }
+    fn outputs(&mut self) -> Option<Vec<IO<Vec<f64>>>> {
+        if self.keys.is_empty() || self.index >= self.n_sample() {
+            return None;
+        }
+        // half a sample of tolerance on the time stamps
+        let time = self.start()? + (self.index as f64 + 0.5) / self.sampling_rate;
+        self.index += 1;
+        let entries = &self.entries;
+        Some(
+            self.keys
+                .iter()
+                .zip(self.cursors.iter_mut())
+                .map(|((tag, name), cursor)| {
+                    let series = &entries[name];
+                    let mut next = cursor.map_or(0, |k| k + 1);
+                    while next < series.len() && series[next].0 < time {
+                        *cursor = Some(next);
+                        next += 1;
+                    }
+                    let data = cursor.map(|k| series[k].1.clone());
+                    IO::<Vec<f64>>::from((tag, data))
+                })
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::jar;
+
+    #[test]
+    fn replay_decimated_keys() {
+        let mut entries = BTreeMap::new();
+        entries.insert(
+            "OSSHardpointD".to_string(),
+            (0..6).map(|k| (k as f64 * 0.1, vec![k as f64])).collect(),
+        );
+        entries.insert(
+            "MCM2RB6D".to_string(),
+            (0..3)
+                .map(|k| (0.1 + k as f64 * 0.2, vec![-(k as f64)]))
+                .collect(),
+        );
+        assert!((sampling_rate(&entries).unwrap() - 10.).abs() < 1e-9);
+        let mut replay = Replay::new(10., entries)
+            .key(jar::OSSHardpointD::new())
+            .unwrap()
+            .key_as(jar::MCM2RB6D::new(), jar::MCM2Lcl6D::new())
+            .unwrap();
+        assert!(Replay::default().key(jar::OSSM1Lcl::new()).is_err());
+        assert!(replay.outputs_tags()[1] == jar::MCM2Lcl6D::new::<()>());
+        assert_eq!(replay.n_sample(), 6);
+        let mut hardpoints = vec![];
+        let mut m2 = vec![];
+        while let Some(y) = replay.outputs() {
+            hardpoints.push(Option::<Vec<f64>>::from(&y[0]).unwrap()[0]);
+            m2.push(Option::<Vec<f64>>::from(&y[1]).map(|x| x[0]));
+        }
+        assert_eq!(hardpoints, vec![0., 1., 2., 3., 4., 5.]);
+        assert_eq!(
+            m2,
+            vec![None, Some(0.), Some(0.), Some(-1.), Some(-1.), Some(-2.)]
+        );
+        assert!(replay.rewind().outputs().is_some());
+    }
+}
@@ -0,0 +1,197 @@
+//! Streaming data logging
+//!
+//! The logged data are buffered in memory and flushed to a log file every `chunk_size` time steps, so long simulations do not hold all their data in memory.
+//!
+//! The log file is appended to, chunk after chunk, and it is made of:
+//!  - 8 bytes: the file signature `DOSTT001`,
+//!  - 8 bytes: the sampling rate in Hz as a little endian `f64`,
+//!  - the records one after the other, each record is:
+//!     - the size in bytes of the [`IO`] name as a little endian `u32` followed by the name (e.g. `OSSM1Lcl`),
+//!     - the time in seconds as a little endian `f64`,
+//!     - the number of values as a little endian `u32` followed by the values as little endian `f64`.
+//!
+//! A truncated last record, e.g. if the simulation was interrupted while writing, is ignored by the reader [`LogReader`].
+//!
+//! # Example
+//! ```no_run
+//! use dos::{io::jar, DataLogging};
+//! use dos::telltale::streaming::LogReader;
+//!
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let mut data = DataLogging::new()
+//!         .sampling_rate(1e3)
+//!         .key(jar::OSSM1Lcl::new())
+//!         .stream("data.log", 1000)?
+//!         .build();
+//!     for _ in 0..10_000 {
+//!         data.step()?;
+//!         data.log(&jar::OSSM1Lcl::with(vec![0f64; 42]))?;
+//!     }
+//!     data.flush()?;
+//!     let m1_rbm = LogReader::open("data.log")?.time_series(jar::OSSM1Lcl::new());
+//!     Ok(())
+//! }
+//! ```
+
+use super::{Result, TellTaleError, TimeSeries};
+use crate::io::{io_name, IO};
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+const SIGNATURE: &[u8; 8] = b"DOSTT001";
+
+/// Log file writer
+pub struct LogWriter {
+    file: BufWriter<File>,
+    /// number of time steps between flushes
+    pub chunk_size: usize,
+}
+impl LogWriter {
+    /// Creates a new log file of data sampled at `sampling_rate` and writes its header
+    pub fn create<P: AsRef<Path>>(path: P, sampling_rate: f64, chunk_size: usize) -> Result<Self> {
+        if chunk_size == 0 {
+            return Err(TellTaleError::Format(
+                "the chunk size must be greater than 0".into(),
+            ));
+        }
+        let mut file = BufWriter::new(File::create(path)?);
+        file.write_all(SIGNATURE)?;
+        file.write_all(&sampling_rate.to_le_bytes())?;
+        file.flush()?;
+        Ok(Self { file, chunk_size })
+    }
+    /// Writes the record of the `data` logged at `time` as `name`
+    pub fn write(&mut self, name: &str, time: f64, data: &[f64]) -> Result<()> {
+        self.file.write_all(&(name.len() as u32).to_le_bytes())?;
+        self.file.write_all(name.as_bytes())?;
+        self.file.write_all(&time.to_le_bytes())?;
+        self.file.write_all(&(data.len() as u32).to_le_bytes())?;
+        for x in data {
+            self.file.write_all(&x.to_le_bytes())?;
+        }
+        Ok(())
+    }
+    /// Flushes the records to the log file
+    pub fn flush(&mut self) -> Result<()> {
+        Ok(self.file.flush()?)
+    }
+}
+
+/// Log file reader
+#[derive(Debug, Clone, Default)]
+pub struct LogReader {
+    /// sampling rate in Hz
+    pub sampling_rate: f64,
+    /// time series of each [`IO`] name
+    pub entries: BTreeMap<String, TimeSeries>,
+}
+impl LogReader {
+    /// Reads a log file
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let mut file = BufReader::new(File::open(path)?);
+        let mut buffer = [0u8; 8];
+        file.read_exact(&mut buffer)?;
+        if &buffer != SIGNATURE {
+            return Err(TellTaleError::Format("not a data log file".into()));
+        }
+        file.read_exact(&mut buffer)?;
+        let sampling_rate = f64::from_le_bytes(buffer);
+        let mut entries: BTreeMap<String, TimeSeries> = BTreeMap::new();
+        while let Some((name, time, data)) = read_record(&mut file) {
+            entries.entry(name).or_default().push((time, data));
+        }
+        Ok(Self {
+            sampling_rate,
+            entries,
+        })
+    }
+    /// Returns the names of the logged [`IO`]
+    pub fn keys(&self) -> Vec<&str> {
+        self.entries.keys().map(|x| x.as_str()).collect()
+    }
+    /// Returns the time series of the [`IO`] named `name`
+    pub fn get(&self, name: &str) -> Option<&TimeSeries> {
+        self.entries.get(name)
+    }
+    /// Returns the time series of the `key` [`IO`]
+    pub fn time_series(&self, key: IO<()>) -> IO<TimeSeries> {
+        (&key, self.entries.get(&io_name(&key)).cloned()).into()
+    }
+}
+
+/// Reads a record, returns `None` at the end of the file or if the record is truncated
+fn read_record<R: Read>(file: &mut R) -> Option<(String, f64, Vec<f64>)> {
+    let mut b4 = [0u8; 4];
+    let mut b8 = [0u8; 8];
+    file.read_exact(&mut b4).ok()?;
+    let mut name = vec![0u8; u32::from_le_bytes(b4) as usize];
+    file.read_exact(&mut name).ok()?;
+    file.read_exact(&mut b8).ok()?;
+    let time = f64::from_le_bytes(b8);
+    file.read_exact(&mut b4).ok()?;
+    let n = u32::from_le_bytes(b4) as usize;
+    let mut data = Vec::with_capacity(n);
+    for _ in 0..n {
+        file.read_exact(&mut b8).ok()?;
+        data.push(f64::from_le_bytes(b8));
+    }
+    Some((String::from_utf8(name).ok()?, time, data))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{io::jar, DataLogging};
+
+    #[test]
+    fn streaming_log() {
+        let path = std::env::temp_dir().join("dos_streaming_log.log");
+        let mut data = DataLogging::new()
+            .sampling_rate(10.)
+            .key(jar::OSSM1Lcl::new())
+            .decimated_key(jar::MCM2Lcl6D::new(), 2)
+            .stream(&path, 3)
+            .unwrap()
+            .build();
+        for k in 0..10 {
+            data.step().unwrap();
+            data.log(&jar::OSSM1Lcl::with(vec![k as f64; 2]))
+                .unwrap()
+                .log(&jar::MCM2Lcl6D::with(vec![-(k as f64)]))
+                .unwrap();
+        }
+        assert!(data.entry(&jar::OSSM1Lcl::new()).unwrap().samples.len() < 10);
+        data.flush().unwrap();
+        let log = super::LogReader::open(&path).unwrap();
+        assert_eq!(log.sampling_rate, 10.);
+        assert_eq!(log.keys(), vec!["MCM2Lcl6D", "OSSM1Lcl"]);
+        let m1: Option<super::TimeSeries> = (&log.time_series(jar::OSSM1Lcl::new())).into();
+        let m1 = m1.unwrap();
+        assert_eq!(m1.len(), 10);
+        assert!((m1[9].0 - 0.9).abs() < 1e-12);
+        assert_eq!(m1[9].1, vec![9.; 2]);
+        let m2 = log.get("MCM2Lcl6D").unwrap();
+        assert_eq!(m2.len(), 5);
+        assert_eq!(m2[4].1, vec![-8.]);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn empty_log() {
+        let path = std::env::temp_dir().join(format!("dos_empty_log_{}.log", std::process::id()));
+        assert!(DataLogging::new().stream(&path, 0).is_err());
+        let _data = DataLogging::new()
+            .sampling_rate(10.)
+            .key(jar::OSSM1Lcl::new())
+            .stream(&path, 3)
+            .unwrap()
+            .build();
+        let log = super::LogReader::open(&path).unwrap();
+        assert_eq!(log.sampling_rate, 10.);
+        assert!(log.entries.is_empty());
+        std::fs::remove_file(path).unwrap();
+    }
+}
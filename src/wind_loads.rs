@@ -16,7 +16,27 @@ use super::{
 use serde;
 use serde::Deserialize;
 use serde_pickle as pkl;
-use std::{fmt, fs::File, io, io::BufReader, path::Path};
+use std::{fmt, fs::File, io, io::BufReader, path::Path, sync::Arc};
+
+#[cfg(feature = "apache-arrow")]
+pub mod apache_arrow;
+pub mod cfd;
+pub mod mapping;
+pub mod resampling;
+pub mod scaling;
+pub mod statistics;
+pub mod streaming;
+pub mod synthetic;
+pub mod transform;
+pub mod windowing;
+#[doc(inline)]
+pub use mapping::LoadsMapping;
+#[doc(inline)]
+pub use scaling::WindSpeedScaling;
+#[doc(inline)]
+pub use statistics::WindLoadsStatistics;
+#[doc(inline)]
+pub use streaming::WindLoadsStream;
 
 #[derive(Debug)]
 pub enum WindLoadsError {
@@ -24,6 +44,12 @@ pub enum WindLoadsError {
     Empty,
     FileNotFound(io::Error),
     PickleRead(serde_pickle::Error),
+    Format(String),
+    Synthetic(String),
+    Nodes(String),
+    FemInputs(String),
+    #[cfg(feature = "apache-arrow")]
+    Parquet(parquet::errors::ParquetError),
     Outputs,
     Inputs,
 }
@@ -34,6 +60,12 @@ impl fmt::Display for WindLoadsError {
             Self::Empty => f.write_str("no data available"),
             Self::FileNotFound(e) => write!(f, "wind loads data file not found: {}", e),
             Self::PickleRead(e) => write!(f, "cannot read wind loads data file: {}", e),
+            Self::Format(e) => write!(f, "invalid wind loads data file format: {}", e),
+            Self::Nodes(e) => write!(f, "invalid wind loads nodes selection: {}", e),
+            Self::FemInputs(e) => write!(f, "wind loads and FEM inputs mismatch: {}", e),
+            Self::Synthetic(e) => write!(f, "cannot generate synthetic wind loads: {}", e),
+            #[cfg(feature = "apache-arrow")]
+            Self::Parquet(e) => write!(f, "cannot read wind loads Parquet file: {}", e),
             Self::Outputs => f.write_str(""),
             Self::Inputs => f.write_str("WindLoading takes no inputs"),
         }
@@ -49,18 +81,25 @@ impl From<serde_pickle::Error> for WindLoadsError {
         Self::PickleRead(e)
     }
 }
+#[cfg(feature = "apache-arrow")]
+impl From<parquet::errors::ParquetError> for WindLoadsError {
+    fn from(e: parquet::errors::ParquetError) -> Self {
+        Self::Parquet(e)
+    }
+}
 impl std::error::Error for WindLoadsError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
             Self::FileNotFound(source) => Some(source),
             Self::PickleRead(source) => Some(source),
+            #[cfg(feature = "apache-arrow")]
+            Self::Parquet(source) => Some(source),
             _ => None,
         }
     }
 }
 
 type Result<T> = std::result::Result<T, WindLoadsError>;
-type Outputs = Option<std::vec::IntoIter<Vec<f64>>>;
 
 macro_rules! loads {
     ($($name:expr, $variant:ident),+) => {
@@ -85,6 +124,51 @@ macro_rules! loads {
                     $(Loads::$variant(io) => io),+
                 }
             }
+            /// Returns a reference to the loads
+            pub fn as_io(&self) -> &Vec<Vec<f64>> {
+                match self {
+                    $(Loads::$variant(io) => io),+
+                }
+            }
+            /// Returns a mutable reference to the loads
+            pub fn as_mut_io(&mut self) -> &mut Vec<Vec<f64>> {
+                match self {
+                    $(Loads::$variant(io) => io),+
+                }
+            }
+            /// Returns the name of the loads in the data files
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Loads::$variant(_) => $name),+
+                }
+            }
+            /// Returns the [`IO`] tag of the loads
+            pub fn tag(&self) -> Tags {
+                match self {
+                    $(Loads::$variant(_) => jar::$variant::new()),+
+                }
+            }
+            /// Returns the name in the data files of the loads matching the [`IO`] tag
+            pub fn tag_name(tag: &Tags) -> Option<&'static str> {
+                $(if *tag == jar::$variant::new::<()>() {
+                    return Some($name);
+                })+
+                None
+            }
+            /// Returns the [`IO`] tag of the loads named `name` in the data files
+            pub fn name_tag(name: &str) -> Option<Tags> {
+                match name {
+                    $($name => Some(jar::$variant::new()),)+
+                    _ => None,
+                }
+            }
+            /// Creates the loads named `name` in the data files
+            pub fn from_name(name: &str, io: Vec<Vec<f64>>) -> Option<Self> {
+                match name {
+                    $($name => Some(Loads::$variant(io)),)+
+                    _ => None,
+                }
+            }
             pub fn decimate(&mut self, decimation_rate: usize) {
                 match self {
                     $(Loads::$variant(io) => {
@@ -131,7 +215,7 @@ loads!(
 /// Wind loads builder
 ///
 /// This structure is used to read the forces and moments time series from a data file and to create the [`WindLoading`] structure
-#[derive(Deserialize)]
+#[derive(Deserialize, Default)]
 pub struct WindLoads {
     /// forces and moments time series
     #[serde(rename = "outputs")]
@@ -141,7 +225,9 @@ pub struct WindLoads {
     #[serde(skip)]
     n_sample: Option<usize>,
     #[serde(skip)]
-    tagged_loads: Vec<IO<std::vec::IntoIter<Vec<f64>>>>,
+    tagged_loads: Vec<(Tags, Vec<Vec<f64>>)>,
+    #[serde(skip)]
+    wind_speed_scaling: Option<WindSpeedScaling>,
 }
 
 impl WindLoads {
@@ -159,6 +245,25 @@ impl WindLoads {
             .find_map(|x| x.as_ref().and_then(|x| Some(x.len())))
             .ok_or(WindLoadsError::Len)
     }
+    /// Returns the number of samples in the time vector, checking that all the loads have the same number of samples
+    fn time_len(&self) -> Result<usize> {
+        let n = self.time.len();
+        match self
+            .loads
+            .iter()
+            .filter_map(|x| x.as_ref())
+            .find(|x| x.len() != n)
+        {
+            Some(load) => Err(WindLoadsError::Format(format!(
+                "{} has {} samples instead of {}",
+                load.name(),
+                load.len(),
+                n
+            ))),
+            None => Ok(n),
+        }
+    }
+    /// Restricts the wind loads to the time range [`t_min`,`t_max`[
     pub fn range(mut self, t_min: f64, t_max: f64) -> Self {
         let min_index = self.time.iter().position(|t| *t >= t_min).unwrap_or(0);
         let max_index = self
@@ -172,8 +277,12 @@ impl WindLoads {
             .for_each(|x| {
                 x.range(min_index, max_index);
             });
+        self.time = self.time[min_index..max_index.max(min_index)].to_vec();
         self
     }
+    /// Keeps every `decimation_rate` sample
+    ///
+    /// The wind loads are not filtered before decimation, see [`resample`](WindLoads::resample) for an anti-aliased alternative
     pub fn decimate(mut self, decimation_rate: usize) -> Self {
         self.loads
             .iter_mut()
@@ -181,20 +290,21 @@ impl WindLoads {
             .for_each(|x| {
                 x.decimate(decimation_rate);
             });
+        self.time = self.time.into_iter().step_by(decimation_rate).collect();
         self
     }
-    fn tagged_load(&self, io: &Tags) -> Result<Outputs> {
+    fn tagged_load(&self, io: &Tags) -> Result<Vec<Vec<f64>>> {
         match &self.n_sample {
             Some(n) => self
                 .loads
                 .iter()
                 .find_map(|x| x.as_ref().and_then(|x| io.ndata(x, *n)))
-                .map_or(Err(WindLoadsError::Empty), |x| Ok(Some(x))),
+                .map_or(Err(WindLoadsError::Empty), |x| Ok(x.collect())),
             None => self
                 .loads
                 .iter()
                 .find_map(|x| x.as_ref().and_then(|x| io.data(x)))
-                .map_or(Err(WindLoadsError::Empty), |x| Ok(Some(x))),
+                .map_or(Err(WindLoadsError::Empty), |x| Ok(x.collect())),
         }
     }
     /// Set the number of time sample
@@ -211,66 +321,47 @@ impl WindLoads {
             ..self
         })
     }
-    /// Selects loads on the truss
-    pub fn truss(mut self) -> Result<Self> {
-        self.tagged_loads.push(IO::OSSTruss6F {
-            data: self.tagged_load(&jar::OSSTruss6F::new())?,
-        });
+    /// Selects the loads corresponding to the `source` [`IO`] tag and outputs them as the `target` [`IO`]
+    pub fn select_as(mut self, source: Tags, target: Tags) -> Result<Self> {
+        let data = self.tagged_load(&source)?;
+        self.tagged_loads.push((target, data));
         Ok(self)
     }
+    /// Selects loads on the truss
+    pub fn truss(self) -> Result<Self> {
+        self.select_as(jar::OSSTruss6F::new(), jar::OSSTruss6F::new())
+    }
     /// Selects loads on the top-end
-    pub fn topend(mut self) -> Result<Self> {
-        self.tagged_loads.push(IO::OSSTopEnd6F {
-            data: self.tagged_load(&jar::OSSTopEnd6F::new())?,
-        });
-        Ok(self)
+    pub fn topend(self) -> Result<Self> {
+        self.select_as(jar::OSSTopEnd6F::new(), jar::OSSTopEnd6F::new())
     }
-    pub fn m2_asm_topend(mut self) -> Result<Self> {
-        self.tagged_loads.push(IO::MCM2TE6F {
-            data: self.tagged_load(&jar::OSSTopEnd6F::new())?,
-        });
-        Ok(self)
+    /// Selects loads on the top-end and outputs them as the ASM top-end loads [`MCM2TE6F`](IO::MCM2TE6F)
+    pub fn m2_asm_topend(self) -> Result<Self> {
+        self.select_as(jar::OSSTopEnd6F::new(), jar::MCM2TE6F::new())
     }
     /// Selects loads on the C-ring
-    pub fn cring(mut self) -> Result<Self> {
-        self.tagged_loads.push(IO::OSSCRING6F {
-            data: self.tagged_load(&jar::OSSCRING6F::new())?,
-        });
-        Ok(self)
+    pub fn cring(self) -> Result<Self> {
+        self.select_as(jar::OSSCRING6F::new(), jar::OSSCRING6F::new())
     }
     /// Selects loads on the GIR
-    pub fn gir(mut self) -> Result<Self> {
-        self.tagged_loads.push(IO::OSSGIR6F {
-            data: self.tagged_load(&jar::OSSGIR6F::new())?,
-        });
-        Ok(self)
+    pub fn gir(self) -> Result<Self> {
+        self.select_as(jar::OSSGIR6F::new(), jar::OSSGIR6F::new())
     }
     /// Selects loads on the M1 cells
-    pub fn m1_cell(mut self) -> Result<Self> {
-        self.tagged_loads.push(IO::OSSCellLcl6F {
-            data: self.tagged_load(&jar::OSSCellLcl6F::new())?,
-        });
-        Ok(self)
+    pub fn m1_cell(self) -> Result<Self> {
+        self.select_as(jar::OSSCellLcl6F::new(), jar::OSSCellLcl6F::new())
     }
     /// Selects loads on the M1 segments
-    pub fn m1_segments(mut self) -> Result<Self> {
-        self.tagged_loads.push(IO::OSSM1Lcl6F {
-            data: self.tagged_load(&jar::OSSM1Lcl6F::new())?,
-        });
-        Ok(self)
+    pub fn m1_segments(self) -> Result<Self> {
+        self.select_as(jar::OSSM1Lcl6F::new(), jar::OSSM1Lcl6F::new())
     }
     /// Selects loads on the M2 segments
-    pub fn m2_segments(mut self) -> Result<Self> {
-        self.tagged_loads.push(IO::MCM2Lcl6F {
-            data: self.tagged_load(&jar::MCM2Lcl6F::new())?,
-        });
-        Ok(self)
+    pub fn m2_segments(self) -> Result<Self> {
+        self.select_as(jar::MCM2Lcl6F::new(), jar::MCM2Lcl6F::new())
     }
-    pub fn m2_asm_reference_bodies(mut self) -> Result<Self> {
-        self.tagged_loads.push(IO::MCM2RB6F {
-            data: self.tagged_load(&jar::MCM2Lcl6F::new())?,
-        });
-        Ok(self)
+    /// Selects loads on the M2 segments and outputs them as the ASM reference bodies loads [`MCM2RB6F`](IO::MCM2RB6F)
+    pub fn m2_asm_reference_bodies(self) -> Result<Self> {
+        self.select_as(jar::MCM2Lcl6F::new(), jar::MCM2RB6F::new())
     }
     /// Selects all loads
     pub fn select_all(self) -> Result<Self> {
@@ -294,9 +385,19 @@ impl WindLoads {
     }
     /// Builds a wind loading source object
     pub fn build(self) -> Result<WindLoading> {
+        let n_sample = self.n_sample.unwrap_or(self.len()?);
+        let sampling_rate = self.sampling_rate().ok();
         Ok(WindLoading {
-            n_sample: self.n_sample.unwrap_or(self.len()?),
-            loads: self.tagged_loads,
+            n_sample,
+            loads: self
+                .tagged_loads
+                .into_iter()
+                .map(|(tag, data)| (tag, Arc::new(data)))
+                .collect(),
+            wind_speed_scaling: self.wind_speed_scaling,
+            time: self.time.into_iter().take(n_sample).collect(),
+            sampling_rate,
+            index: 0,
         })
     }
 }
@@ -304,20 +405,60 @@ impl WindLoads {
 /// Wind loading sources
 ///
 /// This structure contains the time series of wind forces and moments.
-/// The time series implement the [`Iterator`] trait and the [`outputs`](crate::wind_loads::WindLoading::outputs) method step through the iterator
-#[derive(Default)]
+/// The [`outputs`](crate::wind_loads::WindLoading::outputs) method returns the wind loads one time sample after the other, starting from the sample set with [`seek`](WindLoading::seek) or [`rewind`](WindLoading::rewind).
+///
+/// The time series are shared between the clones of a [`WindLoading`], so the same wind loads can be fed to several simulations at once.
+#[derive(Default, Clone)]
 pub struct WindLoading {
-    pub loads: Vec<IO<std::vec::IntoIter<Vec<f64>>>>,
+    /// wind loads time series: `[time][value]`
+    pub loads: Vec<(Tags, Arc<Vec<Vec<f64>>>)>,
     pub n_sample: usize,
+    /// wind speed scaling applied to the CFD wind loads
+    pub wind_speed_scaling: Option<WindSpeedScaling>,
+    /// time vector
+    pub time: Vec<f64>,
+    /// sampling rate in Hz
+    pub sampling_rate: Option<f64>,
+    index: usize,
+}
+impl WindLoading {
+    /// Returns the time in seconds of the last wind loads sample output, or `None` if none have been output yet
+    pub fn time(&self) -> Option<f64> {
+        self.index
+            .checked_sub(1)
+            .and_then(|k| self.time.get(k).cloned())
+    }
+    /// Moves to the 1st wind loads sample at or after `time` in seconds
+    pub fn seek(&mut self, time: f64) -> &mut Self {
+        self.index = self
+            .time
+            .iter()
+            .take(self.n_sample)
+            .position(|t| *t >= time)
+            .unwrap_or(self.n_sample);
+        self
+    }
+    /// Moves back to the 1st wind loads sample
+    pub fn rewind(&mut self) -> &mut Self {
+        self.index = 0;
+        self
+    }
+    /// Returns the number of wind loads samples left
+    pub fn remaining(&self) -> usize {
+        self.n_sample.saturating_sub(self.index)
+    }
 }
 
 /// Wind loading interface
 impl IOTags for WindLoading {
     fn outputs_tags(&self) -> Vec<Tags> {
-        self.loads.iter().map(|x| x.into()).collect()
+        self.loads.iter().map(|(tag, _)| tag.clone()).collect()
     }
     fn inputs_tags(&self) -> Vec<Tags> {
-        unimplemented!("WindLoading takes no inputs")
+        vec![]
+    }
+    fn outputs_rate(&self) -> Option<f64> {
+        self.sampling_rate
     }
 }
 impl DOS for WindLoading {
@@ -325,9 +466,69 @@ impl DOS for WindLoading {
         Err(DOSIOSError::Inputs((WindLoadsError::Inputs).into()))
     }
     fn outputs(&mut self) -> Option<Vec<IO<Vec<f64>>>> {
+        if self.index >= self.n_sample {
+            return None;
+        }
+        let k = self.index;
+        self.index += 1;
         self.loads
-            .iter_mut()
-            .map(|x| -> Option<IO<Vec<f64>>> { x.into() })
+            .iter()
+            .map(|(tag, data)| data.get(k).map(|x| IO::<Vec<f64>>::from((tag, x.clone()))))
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wind_loading_time() {
+        let mut wind_loading = WindLoads {
+            loads: vec![Some(Loads::OSSTruss6F(
+                (0..20).map(|k| vec![k as f64; 18]).collect(),
+            ))],
+            time: (0..20).map(|k| k as f64 * 5e-4).collect(),
+            ..Default::default()
+        }
+        .range(1e-3, 8e-3)
+        .decimate(2)
+        .truss()
+        .unwrap()
+        .build()
+        .unwrap();
+        assert_eq!(wind_loading.outputs_rate(), Some(1e3));
+        assert_eq!(wind_loading.time(), None);
+        wind_loading.outputs();
+        assert_eq!(wind_loading.time(), Some(1e-3));
+        let y = wind_loading.outputs().unwrap();
+        assert_eq!(wind_loading.time(), Some(2e-3));
+        assert_eq!(Option::<Vec<f64>>::from(&y[0]), Some(vec![4.; 18]));
+    }
+
+    #[test]
+    fn wind_loading_seek() {
+        let mut wind_loading = WindLoads {
+            loads: vec![Some(Loads::OSSGIR6F(
+                (0..10).map(|k| vec![k as f64; 6]).collect(),
+            ))],
+            time: (0..10).map(|k| k as f64).collect(),
+            ..Default::default()
+        }
+        .gir()
+        .unwrap()
+        .build()
+        .unwrap();
+        let mut other = wind_loading.clone();
+        assert_eq!(wind_loading.seek(7.5).remaining(), 2);
+        let y = wind_loading.outputs().unwrap();
+        assert_eq!(Option::<Vec<f64>>::from(&y[0]), Some(vec![8.; 6]));
+        wind_loading.outputs();
+        assert!(wind_loading.outputs().is_none());
+        assert_eq!(wind_loading.rewind().remaining(), 10);
+        let y = wind_loading.outputs().unwrap();
+        assert_eq!(Option::<Vec<f64>>::from(&y[0]), Some(vec![0.; 6]));
+        assert_eq!(other.remaining(), 10);
+        assert_eq!(other.seek(100.).remaining(), 0);
+    }
+}
@@ -0,0 +1,192 @@
+//! Wind loads Parquet data files
+//!
+//! A wind loads Parquet data file has a `time` column of `f64` and one column per wind load.
+//! The wind load columns are lists of `f64` named after the wind loads in the CFD pickle data files (e.g. `OSS_TopEnd_6F`, `OSS_Truss_6F`, ...).
+//! Columns with other names are ignored.
+//!
+//! The module also provides the Parquet writer of the wind loads and of the [`TellTale`](crate::telltale::TellTale) time series.
+
+use super::{Loads, Result, WindLoads, WindLoadsError};
+use arrow::{
+    array::{Array, ArrayRef, AsArray, Float64Array, Float64Builder, ListBuilder},
+    datatypes::{DataType, Field, Float64Type, Schema},
+    record_batch::RecordBatch,
+};
+use parquet::{
+    arrow::{arrow_reader::ParquetRecordBatchReaderBuilder, ArrowWriter},
+    errors::ParquetError,
+};
+use std::{fs::File, path::Path, sync::Arc};
+
+const TIME: &str = "time";
+
+fn to_rows(column: &dyn Array) -> Result<Vec<Vec<f64>>> {
+    let row = |x: &dyn Array| -> Vec<f64> { x.as_primitive::<Float64Type>().values().to_vec() };
+    match column.data_type() {
+        DataType::List(field) if field.data_type() == &DataType::Float64 => Ok(column
+            .as_list::<i32>()
+            .iter()
+            .map(|x| x.map_or(vec![], |x| row(&x)))
+            .collect()),
+        DataType::LargeList(field) if field.data_type() == &DataType::Float64 => Ok(column
+            .as_list::<i64>()
+            .iter()
+            .map(|x| x.map_or(vec![], |x| row(&x)))
+            .collect()),
+        DataType::FixedSizeList(field, _) if field.data_type() == &DataType::Float64 => Ok(column
+            .as_fixed_size_list()
+            .iter()
+            .map(|x| x.map_or(vec![], |x| row(&x)))
+            .collect()),
+        data_type => Err(WindLoadsError::Format(format!(
+            "expected a list of f64, found {}",
+            data_type
+        ))),
+    }
+}
+
+impl WindLoads {
+    /// Reads the wind loads from a Parquet file
+    pub fn from_parquet<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let file = File::open(path)?;
+        let reader = ParquetRecordBatchReaderBuilder::try_new(file)?.build()?;
+        let mut time: Vec<f64> = vec![];
+        let mut loads: Vec<(String, Vec<Vec<f64>>)> = vec![];
+        for batch in reader {
+            let batch = batch.map_err(ParquetError::from)?;
+            let schema = batch.schema();
+            for (field, column) in schema.fields().iter().zip(batch.columns()) {
+                let name = field.name();
+                if name == TIME {
+                    match column.data_type() {
+                        DataType::Float64 => {
+                            time.extend_from_slice(column.as_primitive::<Float64Type>().values())
+                        }
+                        data_type => {
+                            return Err(WindLoadsError::Format(format!(
+                                "expected time as f64, found {}",
+                                data_type
+                            )))
+                        }
+                    }
+                } else if Loads::name_tag(name).is_some() {
+                    let mut rows = to_rows(column.as_ref())?;
+                    match loads.iter_mut().find(|(n, _)| n == name) {
+                        Some((_, data)) => data.append(&mut rows),
+                        None => loads.push((name.to_string(), rows)),
+                    }
+                }
+            }
+        }
+        if time.is_empty() {
+            return Err(WindLoadsError::Format(format!(
+                "{} column is missing",
+                TIME
+            )));
+        }
+        Ok(Self {
+            loads: loads
+                .into_iter()
+                .map(|(name, data)| Loads::from_name(&name, data))
+                .collect(),
+            time,
+            ..Default::default()
+        })
+    }
+}
+
+/// Builds a column of lists of `f64` from the rows, a missing row is a null list
+pub(crate) fn list_column<'a, I>(rows: I) -> ArrayRef
+where
+    I: Iterator<Item = Option<&'a [f64]>>,
+{
+    let mut builder = ListBuilder::new(Float64Builder::new());
+    for row in rows {
+        match row {
+            Some(values) => {
+                builder.values().append_slice(values);
+                builder.append(true);
+            }
+            None => builder.append(false),
+        }
+    }
+    Arc::new(builder.finish())
+}
+/// Writes the named columns into a Parquet file
+pub(crate) fn write_parquet<P: AsRef<Path>>(
+    path: P,
+    columns: Vec<(String, ArrayRef)>,
+) -> std::result::Result<(), ParquetError> {
+    let schema = Arc::new(Schema::new(
+        columns
+            .iter()
+            .map(|(name, column)| Field::new(name, column.data_type().clone(), true))
+            .collect::<Vec<Field>>(),
+    ));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        columns.into_iter().map(|(_, column)| column).collect(),
+    )?;
+    let mut writer = ArrowWriter::try_new(File::create(path)?, schema, None)?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+impl WindLoads {
+    /// Writes the wind loads into a Parquet file
+    pub fn to_parquet<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let mut columns: Vec<(String, ArrayRef)> = vec![(
+            TIME.to_string(),
+            Arc::new(Float64Array::from(self.time.clone())),
+        )];
+        columns.extend(self.loads.iter().filter_map(|x| x.as_ref()).map(|x| {
+            (
+                x.name().to_string(),
+                list_column(x.as_io().iter().map(|x| Some(x.as_slice()))),
+            )
+        }));
+        Ok(write_parquet(path, columns)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parquet_round_trip() {
+        let n = 4;
+        let wind_loads = WindLoads {
+            loads: vec![
+                Some(Loads::OSSTopEnd6F(
+                    (0..n).map(|k| vec![k as f64; 6]).collect(),
+                )),
+                Some(Loads::OSSM1Lcl6F(
+                    (0..n).map(|k| vec![-(k as f64); 42]).collect(),
+                )),
+            ],
+            time: (0..n).map(|k| k as f64 * 1e-3).collect(),
+            ..Default::default()
+        };
+        let path = std::env::temp_dir().join("dos_parquet_round_trip.parquet");
+        wind_loads.to_parquet(&path).unwrap();
+        let parquet_loads = WindLoads::from_parquet(&path).unwrap();
+        assert_eq!(parquet_loads.time, wind_loads.time);
+        for (a, b) in parquet_loads.loads.iter().zip(wind_loads.loads.iter()) {
+            let (a, b) = (a.as_ref().unwrap(), b.as_ref().unwrap());
+            assert_eq!(a.name(), b.name());
+            assert_eq!(a.as_io(), b.as_io());
+        }
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn f32_list_column() {
+        let column =
+            arrow::array::ListArray::from_iter_primitive::<arrow::datatypes::Float32Type, _, _>(
+                vec![Some(vec![Some(1f32), Some(2f32)])],
+            );
+        assert!(matches!(to_rows(&column), Err(WindLoadsError::Format(_))));
+    }
+}
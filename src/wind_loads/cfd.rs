@@ -0,0 +1,305 @@
+//! CFD cases catalogue
+//!
+//! The CFD cases are named after the CFD baseline year, the telescope zenith and azimuth angles in degrees, the enclosure configuration and the wind speed in m/s,
+//! e.g. `b2019_30z_135az_cd_12ms` is the 2019 baseline case at 30° zenith angle and 135° azimuth angle with the enclosure vents closed and a 12m/s wind.
+//!
+//! A [`CfdCatalogue`] is either made of the cases of a baseline or of the cases found in a data directory, and it is queried with a [`CfdQuery`].
+//!
+//! # Example
+//! All the closed dome cases at 30° zenith angle with a wind speed greater than 7m/s:
+//! ```no_run
+//! use dos::wind_loads::cfd::{CfdCatalogue, CfdQuery, Enclosure};
+//!
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let catalogue = CfdCatalogue::from_dir("/fsx/Baseline2020")?;
+//!     let query = CfdQuery::new()
+//!         .zenith(30)
+//!         .enclosure(Enclosure::ClosedDome)
+//!         .min_wind_speed(7);
+//!     for case in catalogue.select(&query) {
+//!         println!("{}: {:?}", case, catalogue.path(case));
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use super::{Result, WindLoadsError};
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// Enclosure configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Enclosure {
+    /// open sky: `os`
+    OpenSky,
+    /// closed dome: `cd`
+    ClosedDome,
+}
+impl fmt::Display for Enclosure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Enclosure::OpenSky => f.write_str("os"),
+            Enclosure::ClosedDome => f.write_str("cd"),
+        }
+    }
+}
+
+/// CFD case
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CfdCase {
+    /// baseline year
+    pub baseline: u32,
+    /// zenith angle in degrees
+    pub zenith: u32,
+    /// azimuth angle in degrees
+    pub azimuth: u32,
+    /// enclosure configuration
+    pub enclosure: Enclosure,
+    /// wind speed in m/s
+    pub wind_speed: u32,
+}
+impl CfdCase {
+    /// Creates a new CFD case
+    pub fn new(
+        baseline: u32,
+        zenith: u32,
+        azimuth: u32,
+        enclosure: Enclosure,
+        wind_speed: u32,
+    ) -> Self {
+        Self {
+            baseline,
+            zenith,
+            azimuth,
+            enclosure,
+            wind_speed,
+        }
+    }
+}
+impl fmt::Display for CfdCase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "b{}_{}z_{}az_{}_{}ms",
+            self.baseline, self.zenith, self.azimuth, self.enclosure, self.wind_speed
+        )
+    }
+}
+impl FromStr for CfdCase {
+    type Err = WindLoadsError;
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || WindLoadsError::Format(format!("invalid CFD case name: {}", s));
+        let fields: Vec<&str> = s.split('_').collect();
+        if fields.len() != 5 {
+            return Err(invalid());
+        }
+        let number = |field: &str, prefix: &str, suffix: &str| -> Result<u32> {
+            field
+                .strip_prefix(prefix)
+                .and_then(|x| x.strip_suffix(suffix))
+                .and_then(|x| x.parse::<u32>().ok())
+                .ok_or_else(invalid)
+        };
+        Ok(Self {
+            baseline: number(fields[0], "b", "")?,
+            zenith: number(fields[1], "", "z")?,
+            azimuth: number(fields[2], "", "az")?,
+            enclosure: match fields[3] {
+                "os" => Enclosure::OpenSky,
+                "cd" => Enclosure::ClosedDome,
+                _ => return Err(invalid()),
+            },
+            wind_speed: number(fields[4], "", "ms")?,
+        })
+    }
+}
+
+/// CFD cases query
+///
+/// A query matches the cases satisfying all the criteria that have been set
+#[derive(Debug, Clone, Default)]
+pub struct CfdQuery {
+    baseline: Option<u32>,
+    zenith: Option<u32>,
+    azimuth: Option<u32>,
+    enclosure: Option<Enclosure>,
+    min_wind_speed: Option<u32>,
+    max_wind_speed: Option<u32>,
+}
+impl CfdQuery {
+    /// Creates a query matching all the cases
+    pub fn new() -> Self {
+        Default::default()
+    }
+    /// Matches the baseline year
+    pub fn baseline(self, baseline: u32) -> Self {
+        Self {
+            baseline: Some(baseline),
+            ..self
+        }
+    }
+    /// Matches the zenith angle in degrees
+    pub fn zenith(self, zenith: u32) -> Self {
+        Self {
+            zenith: Some(zenith),
+            ..self
+        }
+    }
+    /// Matches the azimuth angle in degrees
+    pub fn azimuth(self, azimuth: u32) -> Self {
+        Self {
+            azimuth: Some(azimuth),
+            ..self
+        }
+    }
+    /// Matches the enclosure configuration
+    pub fn enclosure(self, enclosure: Enclosure) -> Self {
+        Self {
+            enclosure: Some(enclosure),
+            ..self
+        }
+    }
+    /// Matches the wind speeds strictly greater than `wind_speed` in m/s
+    pub fn min_wind_speed(self, wind_speed: u32) -> Self {
+        Self {
+            min_wind_speed: Some(wind_speed),
+            ..self
+        }
+    }
+    /// Matches the wind speeds strictly less than `wind_speed` in m/s
+    pub fn max_wind_speed(self, wind_speed: u32) -> Self {
+        Self {
+            max_wind_speed: Some(wind_speed),
+            ..self
+        }
+    }
+    /// Returns true if the `case` satisfies the query
+    pub fn matches(&self, case: &CfdCase) -> bool {
+        self.baseline.iter().all(|&x| x == case.baseline)
+            && self.zenith.iter().all(|&x| x == case.zenith)
+            && self.azimuth.iter().all(|&x| x == case.azimuth)
+            && self.enclosure.iter().all(|&x| x == case.enclosure)
+            && self.min_wind_speed.iter().all(|&x| case.wind_speed > x)
+            && self.max_wind_speed.iter().all(|&x| case.wind_speed < x)
+    }
+}
+
+/// CFD cases catalogue
+#[derive(Debug, Clone, Default)]
+pub struct CfdCatalogue {
+    path: PathBuf,
+    cases: Vec<CfdCase>,
+}
+impl CfdCatalogue {
+    /// Creates a catalogue from a list of cases located in the directory `path`
+    pub fn new<P: AsRef<Path>>(path: P, cases: Vec<CfdCase>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            cases,
+        }
+    }
+    /// Creates a catalogue of the 2019 baseline cases located in the directory `path`
+    ///
+    /// The cases are the combinations of the zenith angles 0°, 30° and 60°, the azimuth angles 0°, 45°, 90°, 135° and 180°,
+    /// and the open sky 2m/s and 7m/s and closed dome 12m/s and 17m/s configurations
+    pub fn baseline2019<P: AsRef<Path>>(path: P) -> Self {
+        let mut cases = vec![];
+        for zenith in [0, 30, 60].iter() {
+            for azimuth in [0, 45, 90, 135, 180].iter() {
+                for (enclosure, wind_speed) in [
+                    (Enclosure::OpenSky, 2),
+                    (Enclosure::OpenSky, 7),
+                    (Enclosure::ClosedDome, 12),
+                    (Enclosure::ClosedDome, 17),
+                ]
+                .iter()
+                {
+                    cases.push(CfdCase::new(
+                        2019,
+                        *zenith,
+                        *azimuth,
+                        *enclosure,
+                        *wind_speed,
+                    ));
+                }
+            }
+        }
+        Self::new(path, cases)
+    }
+    /// Creates a catalogue from the cases found in the directory `path`
+    ///
+    /// The cases are the sub-directories named after the CFD cases naming convention, the other entries are ignored
+    pub fn from_dir<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let mut cases: Vec<CfdCase> = std::fs::read_dir(&path)?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| entry.file_name().to_str()?.parse().ok())
+            .collect();
+        cases.sort_by_key(|case: &CfdCase| {
+            (case.baseline, case.zenith, case.azimuth, case.wind_speed)
+        });
+        Ok(Self::new(path, cases))
+    }
+    /// Returns the cases
+    pub fn cases(&self) -> &[CfdCase] {
+        &self.cases
+    }
+    /// Returns the cases that satisfy the `query`
+    pub fn select(&self, query: &CfdQuery) -> Vec<&CfdCase> {
+        self.cases
+            .iter()
+            .filter(|case| query.matches(case))
+            .collect()
+    }
+    /// Returns the case with the same baseline, zenith and azimuth angles than `case` and the wind speed closest to `wind_speed` in m/s
+    pub fn nearest_wind_speed(&self, case: &CfdCase, wind_speed: f64) -> Option<&CfdCase> {
+        self.cases
+            .iter()
+            .filter(|x| {
+                x.baseline == case.baseline && x.zenith == case.zenith && x.azimuth == case.azimuth
+            })
+            .min_by(|a, b| {
+                let da = (a.wind_speed as f64 - wind_speed).abs();
+                let db = (b.wind_speed as f64 - wind_speed).abs();
+                da.partial_cmp(&db).unwrap()
+            })
+    }
+    /// Returns the path to the data directory of the `case`
+    pub fn path(&self, case: &CfdCase) -> PathBuf {
+        self.path.join(case.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cfd_catalogue() {
+        let case: CfdCase = "b2019_30z_135az_cd_12ms".parse().unwrap();
+        assert_eq!(case, CfdCase::new(2019, 30, 135, Enclosure::ClosedDome, 12));
+        assert_eq!(case.to_string(), "b2019_30z_135az_cd_12ms");
+        assert!("b2019_30z_135az_xx_12ms".parse::<CfdCase>().is_err());
+        assert!("b2019_30z_135az_cd".parse::<CfdCase>().is_err());
+        let catalogue = CfdCatalogue::baseline2019("/fsx/Baseline2020");
+        assert_eq!(catalogue.cases().len(), 60);
+        let query = CfdQuery::new()
+            .zenith(30)
+            .enclosure(Enclosure::ClosedDome)
+            .min_wind_speed(7);
+        assert_eq!(catalogue.select(&query).len(), 10);
+        assert_eq!(
+            catalogue
+                .nearest_wind_speed(&case, 8.)
+                .map(|x| x.to_string()),
+            Some("b2019_30z_135az_os_7ms".to_string())
+        );
+        assert_eq!(
+            catalogue.path(&case),
+            Path::new("/fsx/Baseline2020/b2019_30z_135az_cd_12ms")
+        );
+    }
+}
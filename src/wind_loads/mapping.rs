@@ -0,0 +1,165 @@
+//! Wind loads mapping
+//!
+//! The CFD wind loads are mapped to the FEM inputs either with the [`WindLoads`] element selectors (e.g. [`truss`](WindLoads::truss)) or declaratively with a list of [`LoadsMapping`].
+//! A mapping associates a CFD wind loads key (e.g. `OSS_M1_lcl_6F`) to any FEM input [`IO`](crate::IO) and optionally selects a subset of the element nodes.
+//! Each node carries 6 values, 3 forces and 3 moments.
+//!
+//! The size of the wind loads is checked against the size of the FEM inputs with [`WindLoading::check_fem_inputs`].
+//!
+//! # Example
+//! Applying the wind loads of M1 segment #7 only:
+//! ```no_run
+//! use dos::{io::jar, wind_loads::{LoadsMapping, WindLoads}};
+//! use fem::FEM;
+//!
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let fem = FEM::from_pickle("modal_state_space_model_2ndOrder.pkl")?;
+//!     let wind_loading = WindLoads::from_pickle("wind_loads.pkl")?
+//!         .map(&[
+//!             LoadsMapping::new("OSS_Truss_6F", jar::OSSTruss6F::new()),
+//!             LoadsMapping::new("OSS_M1_lcl_6F", jar::OSSM1Lcl6F::new()).nodes(vec![6]),
+//!         ])?
+//!         .build()?;
+//!     wind_loading.check_fem_inputs(&fem)?;
+//!     Ok(())
+//! }
+//! ```
+
+use super::{Loads, Result, WindLoading, WindLoads, WindLoadsError};
+use crate::io::{MatchFEM, Tags};
+
+/// Mapping of CFD wind loads to a FEM input
+#[derive(Debug, Clone)]
+pub struct LoadsMapping {
+    /// CFD wind loads key
+    pub key: String,
+    /// FEM input
+    pub target: Tags,
+    /// indices of the selected nodes, all nodes if `None`
+    pub nodes: Option<Vec<usize>>,
+}
+impl LoadsMapping {
+    /// Creates a new mapping of the wind loads `key` to the FEM input `target`
+    pub fn new<S: Into<String>>(key: S, target: Tags) -> Self {
+        Self {
+            key: key.into(),
+            target,
+            nodes: None,
+        }
+    }
+    /// Selects the nodes of the element
+    pub fn nodes(self, nodes: Vec<usize>) -> Self {
+        Self {
+            nodes: Some(nodes),
+            ..self
+        }
+    }
+}
+
+impl WindLoads {
+    /// Selects the `nodes` of the loads corresponding to the `source` [`IO`](crate::IO) tag and outputs them as the `target` [`IO`](crate::IO)
+    pub fn select_nodes(mut self, source: Tags, target: Tags, nodes: &[usize]) -> Result<Self> {
+        let data = self.tagged_load(&source)?;
+        let n_node = data.first().map_or(0, |x| x.len() / 6);
+        if let Some(node) = nodes.iter().find(|node| **node >= n_node) {
+            return Err(WindLoadsError::Nodes(format!(
+                "node #{} of {:?} is out of range (#nodes: {})",
+                node, source, n_node
+            )));
+        }
+        let data = data
+            .into_iter()
+            .map(|x| {
+                nodes
+                    .iter()
+                    .flat_map(|node| x[node * 6..(node + 1) * 6].to_vec())
+                    .collect()
+            })
+            .collect();
+        self.tagged_loads.push((target, data));
+        Ok(self)
+    }
+    /// Selects the loads according to the `mapping`
+    pub fn map(self, mapping: &[LoadsMapping]) -> Result<Self> {
+        mapping.iter().try_fold(self, |this, m| {
+            let source = Loads::name_tag(&m.key).ok_or_else(|| {
+                WindLoadsError::Format(format!("{} is not a wind loads key", m.key))
+            })?;
+            match &m.nodes {
+                Some(nodes) => this.select_nodes(source, m.target.clone(), nodes),
+                None => this.select_as(source, m.target.clone()),
+            }
+        })
+    }
+}
+
+impl WindLoading {
+    /// Checks that the wind loads match the FEM inputs in number of values
+    pub fn check_fem_inputs(&self, fem: &fem::FEM) -> Result<()> {
+        for (tag, data) in &self.loads {
+            let fem_size: usize = fem
+                .inputs
+                .iter()
+                .find_map(|x| x.as_ref().and_then(|x| tag.match_fem_inputs(x)))
+                .ok_or_else(|| WindLoadsError::FemInputs(format!("{:?} is not a FEM input", tag)))?
+                .iter()
+                .filter_map(|x| match x {
+                    fem::IO::On(io) => Some(io.indices.len()),
+                    fem::IO::Off(_) => None,
+                })
+                .sum();
+            let size = data.first().map_or(0, |x| x.len());
+            if size != fem_size {
+                return Err(WindLoadsError::FemInputs(format!(
+                    "{:?} has {} values but the FEM expects {}",
+                    tag, size, fem_size
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{io::jar, DOS};
+
+    #[test]
+    fn m1_segment_mapping() {
+        let mut wind_loading = WindLoads {
+            loads: vec![Some(Loads::OSSM1Lcl6F(
+                (0..3)
+                    .map(|k| (0..42).map(|i| (k * 100 + i) as f64).collect())
+                    .collect(),
+            ))],
+            time: vec![0., 1., 2.],
+            ..Default::default()
+        }
+        .map(&[
+            LoadsMapping::new("OSS_M1_lcl_6F", jar::OSSM1Lcl6F::new()).nodes(vec![6, 0]),
+            LoadsMapping::new("OSS_M1_lcl_6F", jar::OSSCellLcl6F::new()),
+        ])
+        .unwrap()
+        .build()
+        .unwrap();
+        wind_loading.seek(1.);
+        let y = wind_loading.outputs().unwrap();
+        assert!(y[0] == jar::OSSM1Lcl6F::new::<()>());
+        assert_eq!(
+            Option::<Vec<f64>>::from(&y[0]),
+            Some(vec![
+                136., 137., 138., 139., 140., 141., 100., 101., 102., 103., 104., 105.
+            ])
+        );
+        assert!(y[1] == jar::OSSCellLcl6F::new::<()>());
+        assert_eq!(Option::<Vec<f64>>::from(&y[1]).unwrap().len(), 42);
+        assert!(WindLoads {
+            loads: vec![Some(Loads::OSSM1Lcl6F(vec![vec![0.; 42]]))],
+            time: vec![0.],
+            ..Default::default()
+        }
+        .select_nodes(jar::OSSM1Lcl6F::new(), jar::OSSM1Lcl6F::new(), &[7])
+        .is_err());
+    }
+}
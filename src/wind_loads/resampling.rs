@@ -0,0 +1,181 @@
+//! Wind loads resampling
+//!
+//! The wind loads are resampled at an arbitrary sampling rate with [`WindLoads::resample`]:
+//!  - downsampling: each new sample is computed with a Blackman windowed sinc low-pass FIR filter evaluated at the fractional position of the sample (polyphase filtering), removing the frequencies above the new Nyquist frequency,
+//!  - upsampling: the new samples are linearly interpolated between the original samples.
+//!
+//! The time vector is updated accordingly.
+//!
+//! # Example
+//! Resampling 20Hz CFD wind loads for a 1kHz FEM:
+//! ```no_run
+//! use dos::wind_loads::WindLoads;
+//!
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let wind_loading = WindLoads::from_pickle("wind_loads.pkl")?
+//!         .range(0., 400.)
+//!         .resample(1e3)?
+//!         .select_all()?
+//!         .build()?;
+//!     Ok(())
+//! }
+//! ```
+
+use super::{Loads, Result, WindLoads, WindLoadsError};
+use std::f64::consts::PI;
+
+/// Number of zero crossings on each side of the sinc kernel
+const N_ZERO_CROSSING: f64 = 16.;
+/// Cut-off frequency of the anti-aliasing filter relative to the new Nyquist frequency
+const ROLLOFF: f64 = 0.9;
+
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1e-12 {
+        1f64
+    } else {
+        let x = PI * x;
+        x.sin() / x
+    }
+}
+fn blackman(u: f64) -> f64 {
+    if u.abs() >= 1f64 {
+        0f64
+    } else {
+        0.42 + 0.5 * (PI * u).cos() + 0.08 * (2. * PI * u).cos()
+    }
+}
+/// Returns the index of the 1st sample and the weights of the samples contributing to the new sample at the fractional index `x`
+///
+/// `ratio` is the ratio of the new sampling rate to the original one and `n` is the number of original samples
+fn weights(x: f64, ratio: f64, n: usize) -> (usize, Vec<f64>) {
+    if ratio >= 1f64 {
+        let i = (x.floor() as usize).min(n - 1);
+        if i + 1 < n {
+            let a = x - i as f64;
+            (i, vec![1. - a, a])
+        } else {
+            (i, vec![1f64])
+        }
+    } else {
+        let cutoff = ROLLOFF * ratio;
+        let half_width = N_ZERO_CROSSING / cutoff;
+        let start = (x - half_width).ceil().max(0f64) as usize;
+        let end = ((x + half_width).floor() as usize).min(n - 1);
+        let mut w: Vec<f64> = (start..=end)
+            .map(|i| {
+                let d = x - i as f64;
+                cutoff * sinc(cutoff * d) * blackman(d / half_width)
+            })
+            .collect();
+        // unit DC gain, including at both ends of the time series where the kernel is truncated
+        let sum: f64 = w.iter().sum();
+        w.iter_mut().for_each(|w| *w /= sum);
+        (start, w)
+    }
+}
+
+impl WindLoads {
+    /// Returns the sampling rate in Hz of the wind loads
+    ///
+    /// The time samples are assumed to be evenly spaced
+    pub fn sampling_rate(&self) -> Result<f64> {
+        let n = self.time.len();
+        if n < 2 {
+            return Err(WindLoadsError::Len);
+        }
+        Ok((n - 1) as f64 / (self.time[n - 1] - self.time[0]))
+    }
+    /// Resamples the wind loads at `sampling_rate` in Hz
+    ///
+    /// The wind loads are low-pass filtered if `sampling_rate` is less than the wind loads sampling rate and linearly interpolated otherwise
+    pub fn resample(mut self, sampling_rate: f64) -> Result<Self> {
+        assert!(
+            sampling_rate > 0f64,
+            "the sampling rate must be greater than 0"
+        );
+        let data_rate = self.sampling_rate()?;
+        let n = self.time_len()?;
+        let ratio = sampling_rate / data_rate;
+        let t0 = self.time[0];
+        let duration = self.time[n - 1] - t0;
+        let n_resampled = (duration * sampling_rate + 1e-6).floor() as usize + 1;
+        let mut loads: Vec<&mut Loads> = self.loads.iter_mut().filter_map(|x| x.as_mut()).collect();
+        let mut resampled: Vec<Vec<Vec<f64>>> = vec![Vec::with_capacity(n_resampled); loads.len()];
+        for k in 0..n_resampled {
+            let (start, w) = weights(k as f64 / ratio, ratio, n);
+            for (load, resampled) in loads.iter().zip(resampled.iter_mut()) {
+                let io = load.as_io();
+                let mut sample = vec![0f64; io[start].len()];
+                for (x, w) in io[start..].iter().zip(w.iter()) {
+                    sample
+                        .iter_mut()
+                        .zip(x.iter())
+                        .for_each(|(s, x)| *s += w * x);
+                }
+                resampled.push(sample);
+            }
+        }
+        for (load, resampled) in loads.iter_mut().zip(resampled) {
+            *load.as_mut_io() = resampled;
+        }
+        self.time = (0..n_resampled)
+            .map(|k| t0 + k as f64 / sampling_rate)
+            .collect();
+        self.n_sample = self.n_sample.map(|n| {
+            ((n as f64 * ratio).round() as usize)
+                .max(1)
+                .min(n_resampled)
+        });
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine_loads(sampling_rate: f64, duration: f64, frequencies: &[f64]) -> WindLoads {
+        let time: Vec<f64> = (0..=(duration * sampling_rate).round() as usize)
+            .map(|k| k as f64 / sampling_rate)
+            .collect();
+        WindLoads {
+            loads: vec![Some(Loads::OSSTruss6F(
+                time.iter()
+                    .map(|t| {
+                        vec![frequencies
+                            .iter()
+                            .map(|f| (2. * PI * f * t).sin())
+                            .sum::<f64>()]
+                    })
+                    .collect(),
+            ))],
+            time,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn resample_up_and_down() {
+        // 20Hz -> 1kHz
+        let wind_loads = sine_loads(20., 2., &[1.]).resample(1e3).unwrap();
+        assert_eq!(wind_loads.time.len(), 2001);
+        assert!((wind_loads.sampling_rate().unwrap() - 1e3).abs() < 1e-6);
+        let io = wind_loads.loads[0].as_ref().unwrap().as_io();
+        assert!(wind_loads
+            .time
+            .iter()
+            .zip(io.iter())
+            .all(|(t, x)| (x[0] - (2. * PI * t).sin()).abs() < 2e-2));
+        // 1kHz -> 100Hz: the 200Hz component is filtered out
+        let wind_loads = sine_loads(1e3, 2., &[1., 200.]).resample(1e2).unwrap();
+        assert_eq!(wind_loads.time.len(), 201);
+        let io = wind_loads.loads[0].as_ref().unwrap().as_io();
+        assert!(wind_loads
+            .time
+            .iter()
+            .zip(io.iter())
+            .skip(20)
+            .take(161)
+            .all(|(t, x)| (x[0] - (2. * PI * t).sin()).abs() < 1e-3));
+    }
+}
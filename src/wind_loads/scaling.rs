@@ -0,0 +1,117 @@
+//! Wind speed scaling
+//!
+//! The CFD wind loads computed at a given wind speed $`v_0`$ are scaled to another wind speed $`v`$:
+//!  - the forces and moments are scaled by the ratio of the dynamic pressures $`(v/v_0)^2`$,
+//!  - the time is scaled according to the Strouhal similarity i.e. the frequencies scale with the wind speed and the time with $`v_0/v`$.
+//!
+//! The time scaling changes the sampling rate of the wind loads that may need to be [resampled](crate::wind_loads::WindLoads::resample) afterwards.
+//!
+//! # Example
+//! Wind loads at 10m/s derived from the 12m/s CFD case:
+//! ```no_run
+//! use dos::wind_loads::WindLoads;
+//!
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let wind_loading = WindLoads::from_pickle("b2019_0z_0az_os_12ms.wind_loads_20Hz.pkl")?
+//!         .scale_wind_speed(12., 10.)
+//!         .resample(1e3)?
+//!         .select_all()?
+//!         .build()?;
+//!     println!("{:?}", wind_loading.wind_speed_scaling);
+//!     Ok(())
+//! }
+//! ```
+
+use super::WindLoads;
+use serde::Serialize;
+
+/// Wind speed scaling of the CFD wind loads
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct WindSpeedScaling {
+    /// CFD wind speed in m/s
+    pub cfd_wind_speed: f64,
+    /// scaled wind speed in m/s
+    pub wind_speed: f64,
+}
+impl WindSpeedScaling {
+    /// Returns the forces and moments scaling factor $`(v/v_0)^2`$
+    pub fn loads_scale(&self) -> f64 {
+        (self.wind_speed / self.cfd_wind_speed).powi(2)
+    }
+    /// Returns the time scaling factor $`v_0/v`$
+    pub fn time_scale(&self) -> f64 {
+        self.cfd_wind_speed / self.wind_speed
+    }
+}
+
+impl WindLoads {
+    /// Scales the wind loads computed at the wind speed `cfd_wind_speed` to the wind speed `wind_speed`, both in m/s
+    ///
+    /// Successive scalings are combined and always refer to the wind speed of the CFD case
+    pub fn scale_wind_speed(mut self, cfd_wind_speed: f64, wind_speed: f64) -> Self {
+        assert!(
+            cfd_wind_speed > 0f64 && wind_speed > 0f64,
+            "the wind speeds must be greater than 0"
+        );
+        let scaling = WindSpeedScaling {
+            cfd_wind_speed,
+            wind_speed,
+        };
+        let loads_scale = scaling.loads_scale();
+        self.loads
+            .iter_mut()
+            .filter_map(|x| x.as_mut())
+            .flat_map(|x| x.as_mut_io().iter_mut().flatten())
+            .for_each(|x| *x *= loads_scale);
+        if let Some(t0) = self.time.first().cloned() {
+            let time_scale = scaling.time_scale();
+            self.time
+                .iter_mut()
+                .for_each(|t| *t = t0 + (*t - t0) * time_scale);
+        }
+        self.wind_speed_scaling = Some(match self.wind_speed_scaling {
+            Some(previous) => WindSpeedScaling {
+                cfd_wind_speed: previous.cfd_wind_speed,
+                wind_speed: previous.wind_speed * wind_speed / cfd_wind_speed,
+            },
+            None => scaling,
+        });
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wind_loads::Loads;
+
+    #[test]
+    fn wind_speed_scaling() {
+        let wind_loads = WindLoads {
+            loads: vec![Some(Loads::OSSGIR6F(vec![vec![1f64; 6]; 3]))],
+            time: vec![0., 0.05, 0.1],
+            ..Default::default()
+        }
+        .scale_wind_speed(12., 6.)
+        .scale_wind_speed(1., 2.);
+        let scaling = wind_loads.wind_speed_scaling.unwrap();
+        assert_eq!(scaling.cfd_wind_speed, 12.);
+        assert_eq!(scaling.wind_speed, 12.);
+        assert_eq!(wind_loads.time, vec![0., 0.05, 0.1]);
+        let wind_loads = WindLoads {
+            loads: vec![Some(Loads::OSSGIR6F(vec![vec![1f64; 6]; 3]))],
+            time: vec![0., 0.05, 0.1],
+            ..Default::default()
+        }
+        .scale_wind_speed(12., 6.);
+        assert!(wind_loads.loads[0]
+            .as_ref()
+            .unwrap()
+            .as_io()
+            .iter()
+            .flatten()
+            .all(|x| *x == 0.25));
+        assert_eq!(wind_loads.time, vec![0., 0.1, 0.2]);
+        assert!((wind_loads.sampling_rate().unwrap() - 10.).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,317 @@
+//! Wind loads statistics
+//!
+//! The statistics of the wind loads are computed for each degree of freedom of each element:
+//!  - the mean, the RMS, the standard deviation, the minimum and the maximum,
+//!  - the one-sided power spectral density (PSD) estimated with the Welch method: the time series are divided into segments of `n_fft` samples with 50% overlap, each segment is multiplied by a Hann window and the PSD is the average of the segments periodograms.
+//!
+//! The statistics of the total force and moment about a reference point are added with [`WindLoadsStatistics::total`] given the location of the nodes the wind loads are applied to.
+//!
+//! The statistics are written to pickle or JSON files.
+//!
+//! # Example
+//! ```no_run
+//! use dos::{io::jar, wind_loads::WindLoads};
+//!
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let wind_loads = WindLoads::from_pickle("wind_loads.pkl")?;
+//!     let nodes = vec![(jar::OSSTopEnd6F::new(), vec![[0., 0., 24.]])];
+//!     wind_loads
+//!         .statistics(1024)?
+//!         .total(&wind_loads, &nodes, [0., 0., 0.])?
+//!         .to_json("wind_loads_stats.json")?;
+//!     Ok(())
+//! }
+//! ```
+
+use super::{transform::transfer, Result, WindLoads, WindLoadsError};
+use crate::io::Tags;
+use rustfft::{num_complex::Complex, FftPlanner};
+use serde::Serialize;
+use std::{f64::consts::PI, fs::File, io::BufWriter, path::Path};
+
+/// Statistics of the degrees of freedom of a load
+#[derive(Debug, Clone, Serialize)]
+pub struct Statistics {
+    /// load name
+    pub name: String,
+    pub mean: Vec<f64>,
+    pub rms: Vec<f64>,
+    pub std: Vec<f64>,
+    pub min: Vec<f64>,
+    pub max: Vec<f64>,
+    /// PSDs: `[dof][frequency]`
+    pub psd: Vec<Vec<f64>>,
+}
+impl Statistics {
+    /// Computes the statistics of the time series `[time][dof]` sampled at `sampling_rate` in Hz, the PSDs are computed with segments of `n_fft` samples
+    pub fn new<S: Into<String>>(
+        name: S,
+        series: &[Vec<f64>],
+        sampling_rate: f64,
+        n_fft: usize,
+    ) -> Self {
+        let n = series.len() as f64;
+        let n_dof = series.first().map_or(0, |x| x.len());
+        let dofs: Vec<Vec<f64>> = (0..n_dof)
+            .map(|j| series.iter().map(|x| x[j]).collect())
+            .collect();
+        let mean: Vec<f64> = dofs.iter().map(|x| x.iter().sum::<f64>() / n).collect();
+        let rms: Vec<f64> = dofs
+            .iter()
+            .map(|x| (x.iter().map(|x| x * x).sum::<f64>() / n).sqrt())
+            .collect();
+        let std: Vec<f64> = dofs
+            .iter()
+            .zip(mean.iter())
+            .map(|(x, m)| (x.iter().map(|x| (x - m).powi(2)).sum::<f64>() / n).sqrt())
+            .collect();
+        Self {
+            name: name.into(),
+            min: dofs
+                .iter()
+                .map(|x| x.iter().cloned().fold(f64::INFINITY, f64::min))
+                .collect(),
+            max: dofs
+                .iter()
+                .map(|x| x.iter().cloned().fold(f64::NEG_INFINITY, f64::max))
+                .collect(),
+            psd: dofs
+                .iter()
+                .map(|x| welch(x, sampling_rate, n_fft))
+                .collect(),
+            mean,
+            rms,
+            std,
+        }
+    }
+}
+
+/// Hann windowed periodograms of the segments of the Welch method
+pub(crate) struct Periodogram {
+    n_fft: usize,
+    window: Vec<f64>,
+    fft: std::sync::Arc<dyn rustfft::Fft<f64>>,
+    buffer: Vec<Complex<f64>>,
+}
+impl Periodogram {
+    /// Creates the periodogram of segments of `n_fft` samples
+    pub(crate) fn new(n_fft: usize) -> Self {
+        Self {
+            n_fft,
+            window: (0..n_fft)
+                .map(|i| 0.5 * (1. - (2. * PI * i as f64 / n_fft as f64).cos()))
+                .collect(),
+            fft: FftPlanner::new().plan_fft_forward(n_fft),
+            buffer: vec![Complex::new(0f64, 0f64); n_fft],
+        }
+    }
+    /// Adds the periodogram of the de-meaned `segment` to `psd`
+    pub(crate) fn add(&mut self, segment: &[f64], psd: &mut [f64]) {
+        let mean = segment.iter().sum::<f64>() / self.n_fft as f64;
+        self.buffer
+            .iter_mut()
+            .zip(segment.iter().zip(self.window.iter()))
+            .for_each(|(b, (x, w))| *b = Complex::new((x - mean) * w, 0f64));
+        self.fft.process(&mut self.buffer);
+        psd.iter_mut()
+            .zip(self.buffer.iter())
+            .for_each(|(p, b)| *p += b.norm_sqr());
+    }
+    /// Scales the sum of `n_segment` periodograms into the one-sided PSD
+    pub(crate) fn scale(&self, psd: &mut [f64], sampling_rate: f64, n_segment: usize) {
+        let scale = 1. / (sampling_rate * self.window.iter().map(|w| w * w).sum::<f64>());
+        let n_segment = n_segment.max(1) as f64;
+        let n_fft = self.n_fft;
+        psd.iter_mut().enumerate().for_each(|(k, p)| {
+            // one-sided: doubling all but the zero and Nyquist frequencies
+            let one_sided = if k == 0 || 2 * k == n_fft { 1. } else { 2. };
+            *p *= one_sided * scale / n_segment
+        });
+    }
+}
+
+/// One-sided PSD with the Welch method
+fn welch(x: &[f64], sampling_rate: f64, n_fft: usize) -> Vec<f64> {
+    let mut periodogram = Periodogram::new(n_fft);
+    let mut psd = vec![0f64; n_fft / 2 + 1];
+    let mut n_segment = 0;
+    for segment in x.windows(n_fft).step_by((n_fft / 2).max(1)) {
+        periodogram.add(segment, &mut psd);
+        n_segment += 1;
+    }
+    periodogram.scale(&mut psd, sampling_rate, n_segment);
+    psd
+}
+
+/// Wind loads statistics
+#[derive(Debug, Clone, Serialize)]
+pub struct WindLoadsStatistics {
+    /// sampling rate in Hz
+    pub sampling_rate: f64,
+    /// PSDs frequencies in Hz
+    pub frequency: Vec<f64>,
+    /// statistics of the wind loads of each element
+    pub loads: Vec<Statistics>,
+    /// statistics of the total force and moment about the reference point
+    pub total: Option<Statistics>,
+    /// reference point of the total moment
+    pub reference: Option<[f64; 3]>,
+    #[serde(skip)]
+    n_fft: usize,
+}
+impl WindLoadsStatistics {
+    /// Adds the statistics of the total force and moment about the `reference` point
+    ///
+    /// `nodes` gives for each element the location of its nodes, the element forces and moments are ordered per node as $`[F_x,F_y,F_z,M_x,M_y,M_z]`$
+    pub fn total(
+        self,
+        wind_loads: &WindLoads,
+        nodes: &[(Tags, Vec<[f64; 3]>)],
+        reference: [f64; 3],
+    ) -> Result<Self> {
+        let total = wind_loads.total_loads(nodes, reference)?;
+        Ok(Self {
+            total: Some(Statistics::new(
+                "total",
+                &total,
+                self.sampling_rate,
+                self.n_fft,
+            )),
+            reference: Some(reference),
+            ..self
+        })
+    }
+    /// Returns the statistics of the load named `name`
+    pub fn get(&self, name: &str) -> Option<&Statistics> {
+        self.loads.iter().find(|x| x.name == name)
+    }
+    /// Writes the statistics to a pickle file
+    pub fn to_pickle<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let mut f = BufWriter::new(File::create(path)?);
+        Ok(serde_pickle::to_writer(&mut f, self, true)?)
+    }
+    /// Writes the statistics to a JSON file
+    pub fn to_json<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let f = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(f, self).map_err(|e| WindLoadsError::Format(e.to_string()))
+    }
+}
+
+impl WindLoads {
+    /// Computes the statistics of the wind loads, the PSDs are computed with segments of `n_fft` samples
+    pub fn statistics(&self, n_fft: usize) -> Result<WindLoadsStatistics> {
+        assert!(n_fft > 1, "n_fft must be greater than 1");
+        self.time_len()?;
+        let sampling_rate = self.sampling_rate()?;
+        Ok(WindLoadsStatistics {
+            sampling_rate,
+            frequency: (0..=n_fft / 2)
+                .map(|k| k as f64 * sampling_rate / n_fft as f64)
+                .collect(),
+            loads: self
+                .loads
+                .iter()
+                .filter_map(|x| x.as_ref())
+                .map(|x| Statistics::new(x.name(), x.as_io(), sampling_rate, n_fft))
+                .collect(),
+            total: None,
+            reference: None,
+            n_fft,
+        })
+    }
+    /// Returns the time series of the total force and moment $`[F_x,F_y,F_z,M_x,M_y,M_z]`$ about the `reference` point
+    ///
+    /// `nodes` gives for each element the location of its nodes, the moment of the force $`F`$ applied at the node $`r`$ is $`(r-r_0)\times F`$ where $`r_0`$ is the `reference` point
+    pub fn total_loads(
+        &self,
+        nodes: &[(Tags, Vec<[f64; 3]>)],
+        reference: [f64; 3],
+    ) -> Result<Vec<Vec<f64>>> {
+        let n = self.time_len()?;
+        let mut total = vec![vec![0f64; 6]; n];
+        for (tag, locations) in nodes {
+            let load = self
+                .loads
+                .iter()
+                .filter_map(|x| x.as_ref())
+                .find(|x| x.tag() == *tag)
+                .ok_or(WindLoadsError::Empty)?;
+            let io = load.as_io();
+            if io.iter().any(|x| x.len() != 6 * locations.len()) {
+                return Err(WindLoadsError::Format(format!(
+                    "{} expects {} nodes",
+                    load.name(),
+                    io[0].len() / 6
+                )));
+            }
+            let arms: Vec<[f64; 3]> = locations
+                .iter()
+                .map(|r| {
+                    [
+                        r[0] - reference[0],
+                        r[1] - reference[1],
+                        r[2] - reference[2],
+                    ]
+                })
+                .collect();
+            for (total, x) in total.iter_mut().zip(io.iter()) {
+                for (r, x) in arms.iter().zip(x.chunks(6)) {
+                    total
+                        .iter_mut()
+                        .zip(transfer(x, *r).iter())
+                        .for_each(|(t, x)| *t += x);
+                }
+            }
+        }
+        Ok(total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{io::jar, wind_loads::Loads};
+
+    #[test]
+    fn statistics_and_total_loads() {
+        let n = 4000;
+        let fs = 40.;
+        // 10Hz sinusoid with 2 units of amplitude on Fx and constant unit Fz on 2 nodes
+        let io: Vec<Vec<f64>> = (0..n)
+            .map(|k| {
+                let x = 2. * (2. * PI * 10. * k as f64 / fs).sin();
+                vec![x, 0., 1., 0., 0., 0., x, 0., 1., 0., 0., 0.]
+            })
+            .collect();
+        let wind_loads = WindLoads {
+            loads: vec![Some(Loads::OSSTruss6F(io))],
+            time: (0..n).map(|k| k as f64 / fs).collect(),
+            ..Default::default()
+        };
+        let nodes = vec![(jar::OSSTruss6F::new(), vec![[1., 0., 0.], [-1., 0., 1.]])];
+        let stats = wind_loads
+            .statistics(200)
+            .unwrap()
+            .total(&wind_loads, &nodes, [0., 0., 0.])
+            .unwrap();
+        let truss = stats.get("OSS_Truss_6F").unwrap();
+        assert!(truss.mean[0].abs() < 1e-3);
+        assert!((truss.rms[0] - 2f64.sqrt()).abs() < 1e-3);
+        assert!((truss.max[0] - 2.).abs() < 1e-3);
+        assert_eq!(truss.mean[2], 1.);
+        assert_eq!(truss.std[2], 0.);
+        // the PSD peaks at 10Hz and integrates to the variance
+        let df = stats.frequency[1];
+        let (k_max, _) = truss.psd[0]
+            .iter()
+            .enumerate()
+            .fold((0, 0f64), |a, (k, p)| if *p > a.1 { (k, *p) } else { a });
+        assert!((stats.frequency[k_max] - 10.).abs() < 1e-9);
+        assert!((truss.psd[0].iter().sum::<f64>() * df - 2.).abs() < 1e-2);
+        // total moment about the origin: My = z Fx - x Fz
+        let total = stats.total.unwrap();
+        assert_eq!(total.mean[2], 2.);
+        assert!((total.mean[4] - 0.).abs() < 1e-3);
+        assert!((total.max[4] - 2.).abs() < 1e-3);
+    }
+}
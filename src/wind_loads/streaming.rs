@@ -0,0 +1,418 @@
+//! Streaming wind loads
+//!
+//! The wind loads are read from a columnar data file one chunk of time samples at a time, so only a small fraction of the wind loads is held in memory.
+//!
+//! A columnar data file is written from a [`WindLoads`] with [`WindLoads::to_columnar`] and it is read with [`WindLoadsStream`].
+//! The data file is made of:
+//!  - 8 bytes: the file signature `DOSWL001`,
+//!  - 8 bytes: the size in bytes of the header as a little endian `u64`,
+//!  - the JSON header with the number of time samples and the name and size of each column,
+//!  - the columns one after the other, each column is a time series of vectors of little endian `f64`.
+//!
+//! The first column is the time vector and it is named `time`, the other columns are named after the wind loads in the CFD data files (e.g. `OSS_TopEnd_6F`).
+//!
+//! # Example
+//! ```no_run
+//! use dos::{io::jar, wind_loads::{WindLoads, WindLoadsStream}, DOS};
+//!
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     // one-off conversion of the pickle file
+//!     WindLoads::from_pickle("wind_loads.pkl")?.to_columnar("wind_loads.wlc")?;
+//!     let mut wind_loading = WindLoadsStream::open("wind_loads.wlc")?
+//!         .range(0., 400.)
+//!         .select(jar::OSSTruss6F::new())?
+//!         .select_as(jar::OSSTopEnd6F::new(), jar::MCM2TE6F::new())?;
+//!     while let Some(fem_forces) = wind_loading.outputs() {
+//!         // ...
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use super::{Loads, Result, WindLoads, WindLoadsError};
+use crate::{io::Tags, DOSIOSError, IOTags, DOS, IO};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::{BufWriter, Read, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+const SIGNATURE: &[u8; 8] = b"DOSWL001";
+const TIME: &str = "time";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Column {
+    name: String,
+    size: usize,
+}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Header {
+    n_sample: usize,
+    columns: Vec<Column>,
+}
+
+impl WindLoads {
+    /// Writes the wind loads into a columnar data file
+    pub fn to_columnar<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let loads: Vec<&Loads> = self.loads.iter().filter_map(|x| x.as_ref()).collect();
+        let n_sample = self.time.len();
+        if let Some(load) = loads.iter().find(|x| x.len() != n_sample) {
+            return Err(WindLoadsError::Format(format!(
+                "{} has {} samples instead of {}",
+                load.name(),
+                load.len(),
+                n_sample
+            )));
+        }
+        let mut columns = vec![Column {
+            name: TIME.to_string(),
+            size: 1,
+        }];
+        columns.extend(loads.iter().map(|x| Column {
+            name: x.name().to_string(),
+            size: x.as_io().first().map_or(0, |x| x.len()),
+        }));
+        let header = serde_json::to_vec(&Header { n_sample, columns })
+            .map_err(|e| WindLoadsError::Format(e.to_string()))?;
+        let mut w = BufWriter::new(File::create(path)?);
+        w.write_all(SIGNATURE)?;
+        w.write_all(&(header.len() as u64).to_le_bytes())?;
+        w.write_all(&header)?;
+        for t in &self.time {
+            w.write_all(&t.to_le_bytes())?;
+        }
+        for load in loads {
+            for v in load.as_io().iter().flatten() {
+                w.write_all(&v.to_le_bytes())?;
+            }
+        }
+        w.flush()?;
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct Selection {
+    tag: Tags,
+    offset: u64,
+    size: usize,
+    buffer: Vec<f64>,
+    buffer_start: usize,
+}
+
+/// Wind loading source streamed from a columnar data file
+///
+/// Each selected wind load keeps in memory a chunk of [`chunk_size`](WindLoadsStream::chunk_size) time samples that is read from the data file when needed.
+#[derive(Debug)]
+pub struct WindLoadsStream {
+    file: File,
+    header: Header,
+    data_offset: u64,
+    time: Vec<f64>,
+    start: usize,
+    n_sample: usize,
+    chunk_size: usize,
+    selection: Vec<Selection>,
+    index: usize,
+    error: Option<WindLoadsError>,
+}
+impl WindLoadsStream {
+    /// Opens a columnar data file
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let mut file = File::open(path)?;
+        let mut signature = [0u8; 8];
+        file.read_exact(&mut signature)?;
+        if &signature != SIGNATURE {
+            return Err(WindLoadsError::Format(
+                "not a wind loads columnar data file".to_string(),
+            ));
+        }
+        let mut header_size = [0u8; 8];
+        file.read_exact(&mut header_size)?;
+        let header_size = u64::from_le_bytes(header_size);
+        let file_size = file.metadata()?.len();
+        if header_size > file_size - 16 {
+            return Err(WindLoadsError::Format(format!(
+                "the header size ({} bytes) exceeds the data file size ({} bytes)",
+                header_size, file_size
+            )));
+        }
+        let mut header = vec![0u8; header_size as usize];
+        file.read_exact(&mut header)?;
+        let header: Header =
+            serde_json::from_slice(&header).map_err(|e| WindLoadsError::Format(e.to_string()))?;
+        let data_size = header
+            .columns
+            .iter()
+            .try_fold(16 + header_size, |size, c| {
+                header
+                    .n_sample
+                    .checked_mul(c.size)
+                    .and_then(|n| n.checked_mul(8))
+                    .and_then(|n| size.checked_add(n as u64))
+            })
+            .ok_or_else(|| {
+                WindLoadsError::Format("the data size overflows in the header".to_string())
+            })?;
+        if file_size < data_size {
+            return Err(WindLoadsError::Format(format!(
+                "the data file is truncated: {} bytes instead of {}",
+                file_size, data_size
+            )));
+        }
+        let mut this = Self {
+            file,
+            data_offset: 16 + header_size,
+            time: vec![],
+            start: 0,
+            n_sample: header.n_sample,
+            chunk_size: 1000,
+            selection: vec![],
+            index: 0,
+            error: None,
+            header,
+        };
+        let (offset, size) = this.column(TIME)?;
+        this.time = this.read(offset, size, 0, this.header.n_sample)?;
+        Ok(this)
+    }
+    /// Sets the number of time samples read at once from the data file (default: 1000)
+    pub fn chunk_size(self, chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "chunk_size must be greater than 0");
+        Self { chunk_size, ..self }
+    }
+    /// Restricts the wind loads to the time range [`t_min`,`t_max`[
+    pub fn range(self, t_min: f64, t_max: f64) -> Self {
+        let start = self
+            .time
+            .iter()
+            .position(|t| *t >= t_min)
+            .unwrap_or(self.time.len());
+        let end = self
+            .time
+            .iter()
+            .position(|t| *t >= t_max)
+            .unwrap_or(self.time.len());
+        Self {
+            start,
+            n_sample: end.saturating_sub(start),
+            ..self
+        }
+    }
+    /// Sets the number of time sample
+    pub fn n_sample(self, n_sample: usize) -> Result<Self> {
+        if n_sample == 0 {
+            return Err(WindLoadsError::Format(
+                "n_sample must be greater than 0".to_string(),
+            ));
+        }
+        let n = self.n_sample;
+        if n_sample > n {
+            return Err(WindLoadsError::Format(format!(
+                "n_sample ({}) cannot be greater than the number of sample ({})",
+                n_sample, n
+            )));
+        }
+        Ok(Self { n_sample, ..self })
+    }
+    /// Returns the number of time samples
+    pub fn len(&self) -> usize {
+        self.n_sample
+    }
+    /// Returns true if there is no time sample
+    pub fn is_empty(&self) -> bool {
+        self.n_sample == 0
+    }
+    /// Returns the error that ended the streaming of the wind loads early, if any
+    pub fn error(&self) -> Option<&WindLoadsError> {
+        self.error.as_ref()
+    }
+    /// Returns the time vector
+    pub fn time(&self) -> &[f64] {
+        &self.time[self.start..self.start + self.n_sample]
+    }
+    /// Selects the wind loads corresponding to the [`IO`] tag
+    pub fn select(self, tag: Tags) -> Result<Self> {
+        self.select_as(tag.clone(), tag)
+    }
+    /// Selects the wind loads corresponding to the `source` [`IO`] tag and outputs them as the `target` [`IO`]
+    pub fn select_as(mut self, source: Tags, target: Tags) -> Result<Self> {
+        let name = Loads::tag_name(&source)
+            .ok_or_else(|| WindLoadsError::Format(format!("{:?} is not a wind load", source)))?;
+        let (offset, size) = self.column(name)?;
+        self.selection.push(Selection {
+            tag: target,
+            offset,
+            size,
+            buffer: vec![],
+            buffer_start: 0,
+        });
+        Ok(self)
+    }
+    /// Selects all the wind loads in the data file
+    pub fn select_all(self) -> Result<Self> {
+        let tags: Vec<Tags> = self
+            .header
+            .columns
+            .iter()
+            .filter_map(|c| Loads::name_tag(&c.name))
+            .collect();
+        tags.into_iter()
+            .try_fold(self, |this, tag| this.select(tag))
+    }
+    fn column(&self, name: &str) -> Result<(u64, usize)> {
+        let mut offset = self.data_offset;
+        for column in &self.header.columns {
+            if column.name == name {
+                return Ok((offset, column.size));
+            }
+            offset += (self.header.n_sample * column.size * 8) as u64;
+        }
+        Err(WindLoadsError::Empty)
+    }
+    fn read(&mut self, offset: u64, size: usize, start: usize, n: usize) -> Result<Vec<f64>> {
+        self.file
+            .seek(SeekFrom::Start(offset + (start * size * 8) as u64))?;
+        let mut bytes = vec![0u8; n * size * 8];
+        self.file.read_exact(&mut bytes)?;
+        Ok(bytes
+            .chunks(8)
+            .map(|b| {
+                let mut x = [0u8; 8];
+                x.copy_from_slice(b);
+                f64::from_le_bytes(x)
+            })
+            .collect())
+    }
+    fn sample(&mut self, k: usize) -> Result<Vec<IO<Vec<f64>>>> {
+        let end = self.start + self.n_sample;
+        let chunk_size = self.chunk_size;
+        let mut selection = std::mem::take(&mut self.selection);
+        let outputs = selection
+            .iter_mut()
+            .map(|s| {
+                let n_buffered = s.buffer.len() / s.size.max(1);
+                if k < s.buffer_start || k >= s.buffer_start + n_buffered {
+                    let n = chunk_size.min(end - k);
+                    s.buffer = self.read(s.offset, s.size, k, n)?;
+                    s.buffer_start = k;
+                }
+                let i = (k - s.buffer_start) * s.size;
+                Ok(IO::<Vec<f64>>::from((
+                    &s.tag,
+                    s.buffer[i..i + s.size].to_vec(),
+                )))
+            })
+            .collect();
+        self.selection = selection;
+        outputs
+    }
+}
+
+/// Wind loading interface
+impl IOTags for WindLoadsStream {
+    fn outputs_tags(&self) -> Vec<Tags> {
+        self.selection.iter().map(|s| s.tag.clone()).collect()
+    }
+    fn inputs_tags(&self) -> Vec<Tags> {
+        vec![]
+    }
+    fn outputs_rate(&self) -> Option<f64> {
+        let time = self.time();
+        match time.len() {
+            n if n > 1 => Some((n - 1) as f64 / (time[n - 1] - time[0])),
+            _ => None,
+        }
+    }
+}
+impl DOS for WindLoadsStream {
+    fn inputs(&mut self, _: Vec<IO<Vec<f64>>>) -> std::result::Result<&mut Self, DOSIOSError> {
+        Err(DOSIOSError::Inputs((WindLoadsError::Inputs).into()))
+    }
+    /// Returns the next time sample of the selected wind loads
+    ///
+    /// A read error ends the streaming, it is logged and kept in [`error`](WindLoadsStream::error)
+    fn outputs(&mut self) -> Option<Vec<IO<Vec<f64>>>> {
+        if self.error.is_some() || self.index >= self.n_sample {
+            return None;
+        }
+        let k = self.start + self.index;
+        self.index += 1;
+        match self.sample(k) {
+            Ok(outputs) => Some(outputs),
+            Err(e) => {
+                log::error!("failed to read the wind loads at sample #{}: {}", k, e);
+                self.error = Some(e);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::jar;
+
+    #[test]
+    fn columnar_round_trip() {
+        let n = 5;
+        let wind_loads = WindLoads {
+            loads: vec![
+                Some(Loads::OSSTruss6F(
+                    (0..n).map(|k| vec![k as f64; 3]).collect(),
+                )),
+                None,
+                Some(Loads::OSSGIR6F(
+                    (0..n).map(|k| vec![-(k as f64); 6]).collect(),
+                )),
+            ],
+            time: (0..n).map(|k| k as f64 * 0.5).collect(),
+            ..Default::default()
+        };
+        let path = std::env::temp_dir().join(format!(
+            "dos_columnar_round_trip_{}.wlc",
+            std::process::id()
+        ));
+        wind_loads.to_columnar(&path).unwrap();
+        let mut stream = WindLoadsStream::open(&path)
+            .unwrap()
+            .chunk_size(2)
+            .range(0.5, 2.)
+            .select_as(jar::OSSGIR6F::new(), jar::OSSCRING6F::new())
+            .unwrap()
+            .select(jar::OSSTruss6F::new())
+            .unwrap();
+        assert_eq!(stream.time(), &[0.5, 1., 1.5]);
+        let mut k = 1;
+        while let Some(y) = stream.outputs() {
+            assert!(y[0] == jar::OSSCRING6F::new::<()>());
+            assert_eq!(Option::<Vec<f64>>::from(&y[0]), Some(vec![-(k as f64); 6]));
+            assert_eq!(Option::<Vec<f64>>::from(&y[1]), Some(vec![k as f64; 3]));
+            k += 1;
+        }
+        assert_eq!(k, 4);
+        assert!(stream.error().is_none());
+        let stream = WindLoadsStream::open(&path).unwrap();
+        assert!(stream.range(5., 10.).is_empty());
+        let stream = WindLoadsStream::open(&path).unwrap().range(1., 10.);
+        assert!(stream.n_sample(5).is_err());
+        let stream = WindLoadsStream::open(&path).unwrap().range(0.5, 1.5);
+        assert!(stream.n_sample(3).is_err());
+        let stream = WindLoadsStream::open(&path).unwrap();
+        assert!(matches!(
+            stream.select(jar::MountCmd::new()),
+            Err(WindLoadsError::Format(_))
+        ));
+        // corrupted header size
+        let mut bytes = SIGNATURE.to_vec();
+        bytes.extend_from_slice(&u64::MAX.to_le_bytes());
+        std::fs::write(&path, bytes).unwrap();
+        assert!(matches!(
+            WindLoadsStream::open(&path),
+            Err(WindLoadsError::Format(_))
+        ));
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,295 @@
+//! Synthetic wind loads
+//!
+//! The synthetic wind loads are stationary Gaussian random processes generated from their power spectral densities (PSD) when CFD wind loads are not available.
+//!
+//! Each load [`SyntheticLoad`] is applied on the 6 degrees of freedom (3 forces and 3 moments) of a given number of nodes of an element of the telescope structure.
+//! The loads of a degree of freedom are $`x(t)=\mu+g\,n(t)`$ where $`\mu`$ is the mean, $`g`$ the gain and $`n(t)`$ a random process with the [`Spectrum`] PSD.
+//!
+//! The random processes are generated in the frequency domain with random phases and amplitudes and transformed back in the time domain with an inverse FFT.
+//! The correlation between the loads of different elements is given by a correlation matrix $`C`$: the random spectra of the elements are mixed with the Cholesky factor of $`C`$, so the same degree of freedom of the same node of 2 elements $`i`$ and $`j`$ has the correlation coefficient $`C_{ij}`$.
+//!
+//! The random number generator is seeded so the same seed gives the same wind loads.
+//!
+//! # Example
+//! ```no_run
+//! use dos::{
+//!     io::jar,
+//!     wind_loads::synthetic::{Spectrum, SyntheticLoad, SyntheticWindLoads},
+//! };
+//!
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let spectrum = Spectrum::von_karman(7., 10.);
+//!     let wind_loading = SyntheticWindLoads::new(1e3, 400.)
+//!         .seed(20210405)
+//!         .load(
+//!             SyntheticLoad::new(jar::OSSTopEnd6F::new(), 1, spectrum.clone())
+//!                 .mean([0., 0., 0., 0., 0., 0.])
+//!                 .gain([100., 100., 50., 10., 10., 10.]),
+//!         )
+//!         .load(
+//!             SyntheticLoad::new(jar::OSSTruss6F::new(), 3, spectrum)
+//!                 .gain([500., 500., 200., 50., 50., 50.]),
+//!         )
+//!         .correlation(vec![vec![1., 0.5], vec![0.5, 1.]])
+//!         .generate()?
+//!         .topend()?
+//!         .truss()?
+//!         .build()?;
+//!     Ok(())
+//! }
+//! ```
+
+use super::{Loads, Result, WindLoads, WindLoadsError};
+use crate::io::Tags;
+use nalgebra as na;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rand_distr::StandardNormal;
+use rustfft::{num_complex::Complex, FftPlanner};
+
+/// Power spectral density of the synthetic wind loads
+#[derive(Debug, Clone)]
+pub enum Spectrum {
+    /// Von Kármán spectrum with unit variance: $`S(f)={4T\over(1+70.8(fT)^2)^{5/6}}`$ with $`T=L/U`$
+    VonKarman {
+        /// mean wind speed $`U`$ in m/s
+        wind_speed: f64,
+        /// turbulence length scale $`L`$ in m
+        length_scale: f64,
+    },
+    /// One-sided PSD given as (frequency in Hz, PSD in unit²/Hz) pairs sorted by increasing frequencies, the PSD is linearly interpolated between the frequencies and it is zero outside
+    Table(Vec<(f64, f64)>),
+}
+impl Spectrum {
+    /// Creates a von Kármán spectrum from the wind speed in m/s and the turbulence length scale in m
+    pub fn von_karman(wind_speed: f64, length_scale: f64) -> Self {
+        Spectrum::VonKarman {
+            wind_speed,
+            length_scale,
+        }
+    }
+    /// Returns the one-sided PSD at the frequency `f` in Hz
+    pub fn psd(&self, f: f64) -> f64 {
+        match self {
+            Spectrum::VonKarman {
+                wind_speed,
+                length_scale,
+            } => {
+                let t = length_scale / wind_speed;
+                4. * t / (1. + 70.8 * (f * t).powi(2)).powf(5. / 6.)
+            }
+            Spectrum::Table(table) => table
+                .windows(2)
+                .find(|x| f >= x[0].0 && f <= x[1].0)
+                .map_or(0f64, |x| {
+                    let ((f0, s0), (f1, s1)) = (x[0], x[1]);
+                    if f1 > f0 {
+                        s0 + (s1 - s0) * (f - f0) / (f1 - f0)
+                    } else {
+                        s0
+                    }
+                }),
+        }
+    }
+}
+
+/// Synthetic loads of an element of the telescope structure
+#[derive(Debug, Clone)]
+pub struct SyntheticLoad {
+    tag: Tags,
+    n_node: usize,
+    spectrum: Spectrum,
+    mean: [f64; 6],
+    gain: [f64; 6],
+}
+impl SyntheticLoad {
+    /// Creates the loads on the `n_node` nodes of the element given by the [`IO`](crate::IO) tag of the wind loads (e.g. `jar::OSSTopEnd6F::new()`)
+    pub fn new(tag: Tags, n_node: usize, spectrum: Spectrum) -> Self {
+        Self {
+            tag,
+            n_node,
+            spectrum,
+            mean: [0f64; 6],
+            gain: [1f64; 6],
+        }
+    }
+    /// Sets the mean of the forces and moments (default: 0)
+    pub fn mean(self, mean: [f64; 6]) -> Self {
+        Self { mean, ..self }
+    }
+    /// Sets the gains of the forces and moments (default: 1)
+    pub fn gain(self, gain: [f64; 6]) -> Self {
+        Self { gain, ..self }
+    }
+}
+
+/// Synthetic wind loads builder
+#[derive(Debug, Clone)]
+pub struct SyntheticWindLoads {
+    sampling_rate: f64,
+    duration: f64,
+    seed: u64,
+    loads: Vec<SyntheticLoad>,
+    correlation: Option<Vec<Vec<f64>>>,
+}
+impl SyntheticWindLoads {
+    /// Creates a new synthetic wind loads builder with the sampling rate in Hz and the duration in seconds
+    pub fn new(sampling_rate: f64, duration: f64) -> Self {
+        assert!(
+            sampling_rate > 0f64 && duration > 0f64,
+            "the sampling rate and the duration must be greater than 0"
+        );
+        Self {
+            sampling_rate,
+            duration,
+            seed: 0,
+            loads: vec![],
+            correlation: None,
+        }
+    }
+    /// Sets the seed of the random number generator (default: 0)
+    pub fn seed(self, seed: u64) -> Self {
+        Self { seed, ..self }
+    }
+    /// Adds the loads of an element
+    pub fn load(mut self, load: SyntheticLoad) -> Self {
+        self.loads.push(load);
+        self
+    }
+    /// Sets the correlation matrix of the loads, in the order the loads are added (default: identity)
+    pub fn correlation(self, correlation: Vec<Vec<f64>>) -> Self {
+        Self {
+            correlation: Some(correlation),
+            ..self
+        }
+    }
+    /// Generates the wind loads
+    pub fn generate(self) -> Result<WindLoads> {
+        let n_load = self.loads.len();
+        if n_load == 0 {
+            return Err(WindLoadsError::Empty);
+        }
+        let correlation = match &self.correlation {
+            Some(c) => {
+                if c.len() != n_load || c.iter().any(|c| c.len() != n_load) {
+                    return Err(WindLoadsError::Synthetic(format!(
+                        "expected a {0}x{0} correlation matrix",
+                        n_load
+                    )));
+                }
+                na::DMatrix::from_fn(n_load, n_load, |i, j| c[i][j])
+            }
+            None => na::DMatrix::identity(n_load, n_load),
+        };
+        let l = correlation
+            .cholesky()
+            .ok_or_else(|| {
+                WindLoadsError::Synthetic("the correlation matrix is not positive definite".into())
+            })?
+            .unpack();
+        let names = self
+            .loads
+            .iter()
+            .map(|load| {
+                Loads::tag_name(&load.tag).ok_or_else(|| {
+                    WindLoadsError::Synthetic(format!("{:?} is not a wind load", load.tag))
+                })
+            })
+            .collect::<Result<Vec<&str>>>()?;
+
+        let n = (self.duration * self.sampling_rate).round() as usize + 1;
+        let df = self.sampling_rate / n as f64;
+        let n_channel_max = self.loads.iter().map(|x| 6 * x.n_node).max().unwrap_or(0);
+        // random spectra: [load][channel][frequency]
+        let mut spectra: Vec<Vec<Vec<Complex<f64>>>> = self
+            .loads
+            .iter()
+            .map(|load| vec![vec![Complex::new(0f64, 0f64); n]; 6 * load.n_node])
+            .collect();
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut w = vec![vec![Complex::new(0f64, 0f64); n_channel_max]; n_load];
+        for k in 1..=(n - 1) / 2 {
+            w.iter_mut().flatten().for_each(|w| {
+                *w = Complex::new(rng.sample(StandardNormal), rng.sample(StandardNormal))
+            });
+            let f = k as f64 * df;
+            for (i, (load, spectrum)) in self.loads.iter().zip(spectra.iter_mut()).enumerate() {
+                let amplitude = 0.5 * (load.spectrum.psd(f) * df).sqrt();
+                for (j, s) in spectrum.iter_mut().enumerate() {
+                    let z: Complex<f64> = (0..=i).map(|m| w[m][j] * l[(i, m)]).sum();
+                    s[k] = z * amplitude;
+                    s[n - k] = s[k].conj();
+                }
+            }
+        }
+        let ifft = FftPlanner::new().plan_fft_inverse(n);
+        let loads = self
+            .loads
+            .iter()
+            .zip(spectra)
+            .zip(names)
+            .map(|((load, spectrum), name)| {
+                let channels: Vec<Vec<f64>> = spectrum
+                    .into_iter()
+                    .enumerate()
+                    .map(|(j, mut s)| {
+                        ifft.process(&mut s);
+                        let (mean, gain) = (load.mean[j % 6], load.gain[j % 6]);
+                        s.into_iter().map(|x| mean + gain * x.re).collect()
+                    })
+                    .collect();
+                let io: Vec<Vec<f64>> = (0..n)
+                    .map(|k| channels.iter().map(|x| x[k]).collect())
+                    .collect();
+                Loads::from_name(name, io)
+            })
+            .collect();
+        Ok(WindLoads {
+            loads,
+            time: (0..n).map(|k| k as f64 / self.sampling_rate).collect(),
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::jar;
+
+    #[test]
+    fn von_karman_correlated_loads() {
+        let generate = |seed| {
+            SyntheticWindLoads::new(20., 2000.)
+                .seed(seed)
+                .load(
+                    SyntheticLoad::new(jar::OSSTopEnd6F::new(), 1, Spectrum::von_karman(10., 10.))
+                        .gain([2.; 6]),
+                )
+                .load(
+                    SyntheticLoad::new(jar::OSSTruss6F::new(), 3, Spectrum::von_karman(10., 10.))
+                        .mean([1.; 6]),
+                )
+                .correlation(vec![vec![1., 0.8], vec![0.8, 1.]])
+                .generate()
+                .unwrap()
+        };
+        let wind_loads = generate(7);
+        assert_eq!(wind_loads.time.len(), 40_001);
+        let top_end = wind_loads.loads[0].as_ref().unwrap().as_io();
+        let truss = wind_loads.loads[1].as_ref().unwrap().as_io();
+        assert_eq!(top_end[0].len(), 6);
+        assert_eq!(truss[0].len(), 18);
+        assert_eq!(top_end, generate(7).loads[0].as_ref().unwrap().as_io());
+        let n = top_end.len() as f64;
+        let x: Vec<f64> = top_end.iter().map(|x| x[0]).collect();
+        let y: Vec<f64> = truss.iter().map(|x| x[0]).collect();
+        let y_mean = y.iter().sum::<f64>() / n;
+        let x_var = x.iter().map(|x| x * x).sum::<f64>() / n;
+        let y_var = y.iter().map(|y| (y - y_mean).powi(2)).sum::<f64>() / n;
+        let xy = x.iter().zip(&y).map(|(x, y)| x * (y - y_mean)).sum::<f64>() / n;
+        assert!((y_mean - 1.).abs() < 0.1);
+        assert!((x_var - 4.).abs() < 0.4);
+        assert!((y_var - 1.).abs() < 0.1);
+        assert!((xy / (x_var * y_var).sqrt() - 0.8).abs() < 0.05);
+    }
+}
@@ -0,0 +1,144 @@
+//! Wind loads coordinate transformation
+//!
+//! The CFD forces and moments are given in the CFD coordinate system at reference points chosen for the CFD.
+//! They are transformed into the forces and moments at the FEM nodes and in the FEM nodes local coordinate systems with [`WindLoads::transform`].
+//!
+//! For each node, the moment is transferred from the CFD reference point to the FEM node with $`M'=M+r\times F`$ where $`r`$ is the [offset](NodeTransform::offset) from the FEM node to the CFD reference point,
+//! then both the force and the moment are rotated into the node local coordinate system with the [rotation](NodeTransform::rotation) matrix $`R`$: $`F_l=RF`$ and $`M_l=RM'`$.
+//!
+//! # Example
+//! ```no_run
+//! use dos::{io::jar, wind_loads::{transform::NodeTransform, WindLoads}};
+//!
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let wind_loading = WindLoads::from_pickle("wind_loads.pkl")?
+//!         .transform(
+//!             jar::OSSTopEnd6F::new(),
+//!             &[NodeTransform::new()
+//!                 .offset([0., 0., 0.25])
+//!                 .rotation([[0., 1., 0.], [-1., 0., 0.], [0., 0., 1.]])],
+//!         )?
+//!         .topend()?
+//!         .build()?;
+//!     Ok(())
+//! }
+//! ```
+
+use super::{Result, WindLoads, WindLoadsError};
+use crate::io::Tags;
+
+/// Returns the forces and moments `x` with the moments transferred by the `offset` $`r`$: $`M+r\times F`$
+pub(crate) fn transfer(x: &[f64], r: [f64; 3]) -> [f64; 6] {
+    [
+        x[0],
+        x[1],
+        x[2],
+        x[3] + r[1] * x[2] - r[2] * x[1],
+        x[4] + r[2] * x[0] - r[0] * x[2],
+        x[5] + r[0] * x[1] - r[1] * x[0],
+    ]
+}
+
+/// Coordinate transformation of the forces and moments of a node
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NodeTransform {
+    /// rotation matrix from the CFD coordinate system to the node local coordinate system, row-wise
+    pub rotation: [[f64; 3]; 3],
+    /// vector from the FEM node to the CFD reference point, in the CFD coordinate system
+    pub offset: [f64; 3],
+}
+impl Default for NodeTransform {
+    fn default() -> Self {
+        Self {
+            rotation: [[1., 0., 0.], [0., 1., 0.], [0., 0., 1.]],
+            offset: [0f64; 3],
+        }
+    }
+}
+impl NodeTransform {
+    /// Creates the identity transformation
+    pub fn new() -> Self {
+        Default::default()
+    }
+    /// Sets the rotation matrix
+    pub fn rotation(self, rotation: [[f64; 3]; 3]) -> Self {
+        Self { rotation, ..self }
+    }
+    /// Sets the offset
+    pub fn offset(self, offset: [f64; 3]) -> Self {
+        Self { offset, ..self }
+    }
+    /// Transforms the forces and moments `x` $`[F_x,F_y,F_z,M_x,M_y,M_z]`$
+    pub fn apply(&self, x: &[f64]) -> [f64; 6] {
+        let y = transfer(x, self.offset);
+        let r = &self.rotation;
+        let rotate = |v: &[f64]| -> [f64; 3] {
+            [
+                r[0][0] * v[0] + r[0][1] * v[1] + r[0][2] * v[2],
+                r[1][0] * v[0] + r[1][1] * v[1] + r[1][2] * v[2],
+                r[2][0] * v[0] + r[2][1] * v[1] + r[2][2] * v[2],
+            ]
+        };
+        let (f, m) = (rotate(&y[..3]), rotate(&y[3..]));
+        [f[0], f[1], f[2], m[0], m[1], m[2]]
+    }
+}
+
+impl WindLoads {
+    /// Transforms the forces and moments of the loads corresponding to the `source` [`IO`](crate::IO) tag, with one [`NodeTransform`] per node
+    pub fn transform(mut self, source: Tags, transforms: &[NodeTransform]) -> Result<Self> {
+        let load = self
+            .loads
+            .iter_mut()
+            .filter_map(|x| x.as_mut())
+            .find(|x| x.tag() == source)
+            .ok_or(WindLoadsError::Empty)?;
+        let name = load.name();
+        let io = load.as_mut_io();
+        if let Some(x) = io.iter().find(|x| x.len() != 6 * transforms.len()) {
+            return Err(WindLoadsError::Nodes(format!(
+                "{} has {} nodes but {} transforms are given",
+                name,
+                x.len() / 6,
+                transforms.len()
+            )));
+        }
+        io.iter_mut().for_each(|x| {
+            x.chunks_mut(6)
+                .zip(transforms.iter())
+                .for_each(|(x, t)| x.copy_from_slice(&t.apply(x)))
+        });
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{io::jar, wind_loads::Loads};
+
+    #[test]
+    fn node_transform() {
+        // unit force along x at 1m above the node, rotated by 90deg around z
+        let t = NodeTransform::new().offset([0., 0., 1.]).rotation([
+            [0., 1., 0.],
+            [-1., 0., 0.],
+            [0., 0., 1.],
+        ]);
+        assert_eq!(
+            t.apply(&[1., 0., 0., 0., 0., 0.]),
+            [0., -1., 0., 1., 0., 0.]
+        );
+        let wind_loads = WindLoads {
+            loads: vec![Some(Loads::OSSTruss6F(vec![
+                vec![1., 0., 0., 0., 0., 0.];
+                2
+            ]))],
+            time: vec![0., 1.],
+            ..Default::default()
+        };
+        assert!(wind_loads
+            .transform(jar::OSSTruss6F::new(), &[t, t])
+            .is_err());
+    }
+}
@@ -0,0 +1,180 @@
+//! Wind loads windowing
+//!
+//! The wind loads time series are shaped before the simulation with:
+//!  - [`ramp_in`](WindLoads::ramp_in): the loads are ramped in smoothly from 0 to avoid exciting the telescope structure with a step of the mean wind loads,
+//!  - [`remove_mean`](WindLoads::remove_mean): the mean of the loads is subtracted, by default the mean is kept,
+//!  - [`looping`](WindLoads::looping): the wind loads are repeated until the requested duration is reached, successive repetitions are cross-faded at the seam.
+//!
+//! # Example
+//! Driving a 30mn simulation with a 400s CFD record:
+//! ```no_run
+//! use dos::wind_loads::WindLoads;
+//!
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let wind_loading = WindLoads::from_pickle("wind_loads.pkl")?
+//!         .range(0., 400.)
+//!         .looping(1800., 10.)?
+//!         .ramp_in(5.)
+//!         .resample(1e3)?
+//!         .select_all()?
+//!         .build()?;
+//!     Ok(())
+//! }
+//! ```
+
+use super::{Result, WindLoads, WindLoadsError};
+use std::f64::consts::PI;
+
+/// Raised cosine going from 0 to 1 for `x` going from 0 to 1
+fn raised_cosine(x: f64) -> f64 {
+    0.5 * (1. - (PI * x.clamp(0f64, 1f64)).cos())
+}
+
+impl WindLoads {
+    /// Ramps in the wind loads over the first `duration` seconds with a raised cosine
+    pub fn ramp_in(mut self, duration: f64) -> Self {
+        assert!(duration > 0f64, "the ramp duration must be greater than 0");
+        let t0 = match self.time.first() {
+            Some(t0) => *t0,
+            None => return self,
+        };
+        let n_ramp = self.time.iter().take_while(|t| **t - t0 < duration).count();
+        let weights: Vec<f64> = self.time[..n_ramp]
+            .iter()
+            .map(|t| raised_cosine((t - t0) / duration))
+            .collect();
+        self.loads
+            .iter_mut()
+            .filter_map(|x| x.as_mut())
+            .for_each(|x| {
+                x.as_mut_io()
+                    .iter_mut()
+                    .zip(weights.iter())
+                    .for_each(|(x, w)| x.iter_mut().for_each(|x| *x *= w))
+            });
+        self
+    }
+    /// Subtracts from each load its mean over the time series
+    pub fn remove_mean(mut self) -> Self {
+        self.loads
+            .iter_mut()
+            .filter_map(|x| x.as_mut())
+            .map(|x| x.as_mut_io())
+            .filter(|io| !io.is_empty())
+            .for_each(|io| {
+                let n = io.len() as f64;
+                let mean = io.iter().fold(vec![0f64; io[0].len()], |mut m, x| {
+                    m.iter_mut().zip(x.iter()).for_each(|(m, x)| *m += x / n);
+                    m
+                });
+                io.iter_mut()
+                    .for_each(|x| x.iter_mut().zip(mean.iter()).for_each(|(x, m)| *x -= m));
+            });
+        self
+    }
+    /// Repeats the wind loads up to `duration` seconds
+    ///
+    /// Each repetition starts `crossfade` seconds before the end of the previous one and the loads are cross-faded over that period of time.
+    /// The 1st repetition starts with the original wind loads and all the following ones start with the cross-fading from the end to the beginning of the original wind loads.
+    pub fn looping(mut self, duration: f64, crossfade: f64) -> Result<Self> {
+        let n = self.time_len()?;
+        let sampling_rate = self.sampling_rate()?;
+        let n_crossfade = (crossfade * sampling_rate).round() as usize;
+        if 2 * n_crossfade > n {
+            return Err(WindLoadsError::Format(format!(
+                "the cross-fading ({}s) cannot be longer than half the wind loads duration",
+                crossfade
+            )));
+        }
+        let n_looped = (duration * sampling_rate).round() as usize + 1;
+        // period of the loop
+        let n_period = n - n_crossfade;
+        let weights: Vec<f64> = (0..n_crossfade)
+            .map(|i| raised_cosine((i as f64 + 0.5) / n_crossfade as f64))
+            .collect();
+        self.loads
+            .iter_mut()
+            .filter_map(|x| x.as_mut())
+            .for_each(|x| {
+                let io = x.as_mut_io();
+                let period: Vec<Vec<f64>> = io[..n_period]
+                    .iter()
+                    .enumerate()
+                    .map(|(i, x)| match weights.get(i) {
+                        Some(w) => x
+                            .iter()
+                            .zip(io[n_period + i].iter())
+                            .map(|(x, y)| w * x + (1. - w) * y)
+                            .collect(),
+                        None => x.clone(),
+                    })
+                    .collect();
+                *io = (0..n_looped)
+                    .map(|k| {
+                        if k < n_period {
+                            io[k].clone()
+                        } else {
+                            period[k % n_period].clone()
+                        }
+                    })
+                    .collect();
+            });
+        let t0 = self.time[0];
+        self.time = (0..n_looped)
+            .map(|k| t0 + k as f64 / sampling_rate)
+            .collect();
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wind_loads::Loads;
+
+    #[test]
+    fn ramp_and_loop() {
+        let n = 100;
+        let wind_loads = WindLoads {
+            loads: vec![Some(Loads::OSSTopEnd6F(
+                (0..n).map(|k| vec![1. + k as f64; 6]).collect(),
+            ))],
+            time: (0..n).map(|k| k as f64 * 0.1).collect(),
+            ..Default::default()
+        }
+        .looping(25., 1.)
+        .unwrap()
+        .ramp_in(1.);
+        assert_eq!(wind_loads.time.len(), 251);
+        let io = wind_loads.loads[0].as_ref().unwrap().as_io();
+        assert_eq!(io[0][0], 0.);
+        assert_eq!(io[10][0], 11.);
+        assert_eq!(io[89][0], 90.);
+        // the cross-fading starts from the end of the record
+        assert!((io[90][0] - 91.).abs() < 1.);
+        assert!((io[99][0] - 10.).abs() < 1.);
+        assert_eq!(io[100][0], 11.);
+        let max_jump = io
+            .windows(2)
+            .skip(10)
+            .map(|x| (x[1][0] - x[0][0]).abs())
+            .fold(0f64, f64::max);
+        // instead of the 90 jump of the original record at the seam
+        assert!(max_jump < 15.);
+    }
+
+    #[test]
+    fn remove_mean() {
+        let wind_loads = WindLoads {
+            loads: vec![
+                Some(Loads::OSSTopEnd6F(vec![vec![1.; 6], vec![3.; 6]])),
+                Some(Loads::OSSTruss6F(vec![])),
+            ],
+            time: vec![0., 1.],
+            ..Default::default()
+        }
+        .remove_mean();
+        let io = wind_loads.loads[0].as_ref().unwrap().as_io();
+        assert_eq!(io, &[vec![-1.; 6], vec![1.; 6]]);
+    }
+}
@@ -0,0 +1,101 @@
+//! Golden trajectory regression tests of the Simulink controllers
+//!
+//! The golden trajectories are regression snapshots self-recorded from the controllers themselves with
+//! ```shell
+//! cargo test --test golden -- --ignored
+//! ```
+
+use dos::controllers::{golden::Golden, m1, mount};
+use std::path::{Path, PathBuf};
+
+const N_STEP: usize = 40;
+const TOLERANCE: f64 = 1e-9;
+
+fn golden_path(controller: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("golden")
+        .join(controller)
+        .with_extension("json")
+}
+/// Sinusoidal inputs of the given sizes and amplitudes
+fn inputs(sizes_amplitudes: &[(usize, f64)]) -> Vec<Vec<Vec<f64>>> {
+    (0..N_STEP)
+        .map(|k| {
+            sizes_amplitudes
+                .iter()
+                .enumerate()
+                .map(|(i, (n, a))| {
+                    (0..*n)
+                        .map(|j| a * (0.05 * k as f64 + 0.3 * (i + j) as f64).sin())
+                        .collect()
+                })
+                .collect()
+        })
+        .collect()
+}
+
+macro_rules! golden {
+    ($(($test:ident, $controller:path, $name:expr, $sizes:expr)),+) => {
+        $(
+        #[test]
+        fn $test() {
+            let mut controller = <$controller>::new();
+            Golden::from_json(golden_path($name))
+                .unwrap()
+                .compare(&mut controller, TOLERANCE)
+                .unwrap();
+        }
+        )+
+        #[test]
+        #[ignore]
+        fn record_golden_trajectories() {
+            $(
+            let mut controller = <$controller>::new();
+            Golden::record($name, &mut controller, inputs(&$sizes))
+                .unwrap()
+                .to_json(golden_path($name))
+                .unwrap();
+            )+
+        }
+    };
+}
+
+golden!(
+    (
+        mount_control,
+        mount::controller::Controller,
+        "MountControl0",
+        [(8, 1e-6), (8, 1e-6), (4, 1e-6)]
+    ),
+    (
+        mount_drives,
+        mount::drives::Controller,
+        "MountDrives",
+        [(3, 1e-3), (8, 1e-6), (8, 1e-6), (4, 1e-6)]
+    ),
+    (
+        pdr_mount_control,
+        mount::pdr::controller::Controller,
+        "Mount_Control",
+        [(4, 1e-6), (6, 1e-6), (4, 1e-6)]
+    ),
+    (
+        pdr_mount_drives,
+        mount::pdr::drives::Controller,
+        "Mount_Drv_PDR2021",
+        [(3, 1e-3), (4, 1e-6), (6, 1e-6), (4, 1e-6)]
+    ),
+    (
+        m1_hp_load_cells,
+        m1::hp_load_cells::Controller,
+        "M1HPloadcells",
+        [(84, 1e-6), (42, 1e-6)]
+    ),
+    (
+        m1_cg_controller,
+        m1::cg_controller::Controller,
+        "M1OFL_Control",
+        [(42, 1e2)]
+    )
+);